@@ -0,0 +1,53 @@
+//! Generates a test per example fixture and per part with a known answer, see `src/runner.rs`
+
+use std::{fmt::Write, path::Path};
+
+fn file_names(dir: &Path) -> Vec<String> {
+    let mut names = std::fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok()?.file_name().into_string().ok())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+fn main() {
+    println!("cargo:rerun-if-changed=input");
+
+    let mut out = String::new();
+    for year in file_names(Path::new("input")) {
+        if year.parse::<u32>().is_err() {
+            continue;
+        }
+        let dir = Path::new("input").join(&year).join("examples");
+        println!("cargo:rerun-if-changed={}", dir.display());
+        writeln!(out, "mod y{year} {{").unwrap();
+        for name in file_names(&dir) {
+            let Some(name) = name.strip_suffix(".txt") else {
+                continue;
+            };
+            let file = std::fs::read_to_string(dir.join(format!("{name}.txt"))).unwrap();
+            let header = file.split_once("---\n").map_or("", |h| h.0);
+            for line in header.lines() {
+                let Some(part) = line
+                    .split_once(':')
+                    .and_then(|(k, _)| k.trim().strip_prefix("part"))
+                    .filter(|p| p.parse::<u32>().is_ok())
+                else {
+                    continue;
+                };
+                let test = format!("{}_part{part}", name.replace('-', "_"));
+                writeln!(
+                    out,
+                    "#[test]\nfn {test}() {{\n    crate::runner::check_example({year}, {name:?}, {part});\n}}\n"
+                )
+                .unwrap();
+            }
+        }
+        writeln!(out, "}}").unwrap();
+    }
+
+    let dest = Path::new(&std::env::var("OUT_DIR").unwrap()).join("examples.rs");
+    std::fs::write(dest, out).unwrap();
+}
@@ -0,0 +1,222 @@
+//! Differential testing for days with several solutions to the same part
+//!
+//! A day that wants to be checked provides a [`Fuzz`]: a generator for random valid inputs and a
+//! way to make a given input smaller. Every generated input is fed to every registered solution
+//! for a part, first disagreement or panic is shrunk down and reported.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::runner::{Ctx, Overrides, Solution, Year};
+
+/// SplitMix64, good enough to make inputs and not worth a dependency
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// Random number in `0..n`
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// Random number in `range`
+    pub fn range(&mut self, range: std::ops::RangeInclusive<usize>) -> usize {
+        range.start() + self.below(range.end() - range.start() + 1)
+    }
+
+    pub fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+
+    /// true with probability `1 / n`
+    pub fn one_in(&mut self, n: usize) -> bool {
+        self.below(n) == 0
+    }
+}
+
+pub struct Fuzz {
+    pub day: u32,
+    pub generate: fn(&mut Rng) -> String,
+    /// Slightly smaller versions of an input, they should still be valid inputs
+    pub shrink: fn(&str) -> Vec<String>,
+    /// Parameters every generated input runs with, `--param` takes priority
+    pub params: &'static [(&'static str, &'static str)],
+}
+
+/// Inputs with one of the lines removed
+pub fn shrink_lines(input: &str) -> Vec<String> {
+    shrink_by(input, "\n")
+}
+
+/// Inputs with one of the space separated words removed
+pub fn shrink_words(input: &str) -> Vec<String> {
+    shrink_by(input, " ")
+}
+
+/// Inputs with one of the blank line separated blocks removed
+pub fn shrink_blocks(input: &str) -> Vec<String> {
+    shrink_by(input, "\n\n")
+}
+
+fn shrink_by(input: &str, sep: &str) -> Vec<String> {
+    let items = input.split(sep).collect::<Vec<_>>();
+    (0..items.len())
+        .filter(|_| items.len() > 1)
+        .map(|skip| {
+            let rest = items
+                .iter()
+                .enumerate()
+                .filter_map(|(ix, i)| (ix != skip).then_some(*i));
+            rest.collect::<Vec<_>>().join(sep)
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+pub struct Disagreement {
+    pub day: u32,
+    pub part: u32,
+    pub input: String,
+    /// Solution name along with its answer or a panic message
+    pub answers: Vec<(String, Result<String, String>)>,
+}
+
+impl std::fmt::Display for Disagreement {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Day {} - Part {} disagrees on", self.day, self.part)?;
+        writeln!(f, "{}", self.input)?;
+        for (name, answer) in &self.answers {
+            match answer {
+                Ok(answer) => writeln!(f, "\t{name}: {answer}")?,
+                Err(panic) => writeln!(f, "\t{name}: panicked: {panic}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+fn run(solution: &Solution, input: &str, params: &Overrides) -> Result<String, String> {
+    let ctx = Ctx::new(params.clone());
+    catch_unwind(AssertUnwindSafe(|| (solution.run)(input, &ctx))).map_err(|e| {
+        e.downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| e.downcast_ref::<String>().cloned())
+            .unwrap_or_default()
+    })
+}
+
+/// Answers from all the solutions if they disagree or any of them panics
+fn disagree(
+    solutions: &[&Solution],
+    input: &str,
+    params: &Overrides,
+) -> Option<Vec<(String, Result<String, String>)>> {
+    let answers = solutions
+        .iter()
+        .map(|s| (s.to_string(), run(s, input, params)))
+        .collect::<Vec<_>>();
+    answers
+        .iter()
+        .any(|a| a.1.is_err() || a.1 != answers[0].1)
+        .then_some(answers)
+}
+
+/// Check `cases` random inputs, returns the smallest disagreeing input for the first failure
+pub fn differential(
+    year: &Year,
+    fuzz: &Fuzz,
+    cli: &Overrides,
+    seed: u64,
+    cases: usize,
+) -> Result<(), Disagreement> {
+    let mut params = Overrides::default();
+    for (key, value) in fuzz.params {
+        params.set(key, value);
+    }
+    params.extend(cli);
+    let params = &params;
+    let mut rng = Rng::new(seed);
+    let mut parts = year
+        .solutions()
+        .filter(|s| s.day == fuzz.day)
+        .map(|s| s.part)
+        .collect::<Vec<_>>();
+    parts.dedup();
+
+    for _ in 0..cases {
+        let input = (fuzz.generate)(&mut rng);
+        for &part in &parts {
+            let solutions = year
+                .solutions()
+                .filter(|s| s.day == fuzz.day && s.part == part)
+                .collect::<Vec<_>>();
+
+            let Some(mut answers) = disagree(&solutions, &input, params) else {
+                continue;
+            };
+
+            let mut input = input;
+            'shrink: loop {
+                for smaller in (fuzz.shrink)(&input) {
+                    if let Some(a) = disagree(&solutions, &smaller, params) {
+                        input = smaller;
+                        answers = a;
+                        continue 'shrink;
+                    }
+                }
+                break;
+            }
+            return Err(Disagreement {
+                day: fuzz.day,
+                part,
+                input,
+                answers,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn shrink_works() {
+    assert_eq!(shrink_lines("a\nb\nc"), ["b\nc", "a\nc", "a\nb"]);
+    assert_eq!(shrink_blocks("a\nb\n\nc"), ["c", "a\nb"]);
+    assert_eq!(shrink_words("1 2"), ["2", "1"]);
+    assert!(shrink_lines("a").is_empty());
+}
+
+#[test]
+fn panics_count_as_failures() {
+    let hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(|_| {}));
+    let fine = Solution::new(1, 1, |i, _| i.len().to_string());
+    let broken = Solution::new(1, 1, |_, _| panic!("broken"));
+    let params = Overrides::default();
+    assert!(disagree(&[&fine, &fine], "abc", &params).is_none());
+    // same panic from every solution is still a failure
+    let answers = disagree(&[&broken, &broken], "abc", &params);
+    std::panic::set_hook(hook);
+    assert_eq!(answers.unwrap()[0].1, Err("broken".to_owned()));
+}
+
+#[test]
+fn variants_agree() {
+    for year in crate::runner::YEARS {
+        for fuzz in year.fuzz {
+            if let Err(err) = differential(year, fuzz, &Overrides::default(), 2024, 100) {
+                panic!("{err}");
+            }
+        }
+    }
+}
@@ -1,25 +1,7 @@
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-use aoc_runner_derive::aoc_lib;
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
+pub mod fuzz;
+pub mod runner;
+pub mod trace;
+mod y2024;
 
 impl std::fmt::Debug for TwoDee<usize> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -399,11 +381,15 @@ impl std::ops::Mul<usize> for Point {
     }
 }
 
+impl From<Point> for (i32, i32) {
+    fn from(value: Point) -> Self {
+        (value.x, value.y)
+    }
+}
+
 impl From<Point> for (usize, usize) {
     fn from(value: Point) -> Self {
         let v = value.guard(1_000_000).unwrap();
         (v.x as usize, v.y as usize)
     }
 }
-
-aoc_lib! { year = 2024 }
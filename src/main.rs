@@ -1,3 +1,273 @@
-use aoc_runner_derive::aoc_main;
+use std::{
+    process::ExitCode,
+    time::{Duration, Instant},
+};
 
-aoc_main! { lib = aoc2024 }
+use aoc::{
+    fuzz,
+    runner::{self, Ctx, Overrides, Solution},
+    trace::{Level, Trace, Visualizer, Writer},
+};
+
+const USAGE: &str = "\
+Usage: aoc [DAY]... [--year YEAR] [--example] [--param KEY=VALUE]... [TRACING]
+       aoc [DAY]... [--year YEAR] --fuzz SEED [--cases N]
+       aoc [--year YEAR] TOOL [ARG]...
+
+Runs all the solutions for given days, or for every day if none are given
+
+    --year YEAR          Pick solutions for this year, defaults to the latest one
+    --example            Run on example inputs from input/<year>/examples instead,
+                         only parts with a known answer are checked
+    --param KEY=VALUE    Override a puzzle parameter such as grid size,
+                         takes priority over values from example headers
+    --fuzz SEED          Feed random inputs to days with several solutions
+                         for the same part and report the smallest input
+                         they disagree on
+    --cases N            Number of random inputs to try, defaults to 1000
+
+Tracing:
+    --trace LEVEL        Print what solvers are doing to stderr, LEVEL is one
+                         of info, debug or trace, from least to most verbose
+    --trace-file PATH    Write trace to a file instead, level defaults to info
+    --visualize MS       Animate grids in the terminal with a pause between
+                         frames, level defaults to debug
+";
+
+struct Args {
+    year: Option<u32>,
+    days: Vec<u32>,
+    example: bool,
+    params: Overrides,
+    fuzz: Option<u64>,
+    cases: usize,
+    trace: Option<Level>,
+    trace_file: Option<String>,
+    visualize: Option<u64>,
+    /// Tool name and its arguments
+    tool: Option<(String, Vec<String>)>,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args {
+        year: None,
+        days: Vec::new(),
+        example: false,
+        params: Overrides::default(),
+        fuzz: None,
+        cases: 1000,
+        trace: None,
+        trace_file: None,
+        visualize: None,
+        tool: None,
+    };
+    let mut raw = std::env::args().skip(1);
+    while let Some(arg) = raw.next() {
+        match arg.as_str() {
+            "--example" => args.example = true,
+            "--year" => {
+                let year = raw.next().ok_or("--year needs a value")?;
+                let year = year.parse().map_err(|_| format!("Bad year: {year}"))?;
+                args.year = Some(year);
+            }
+            "--param" => {
+                let param = raw.next().ok_or("--param needs a value")?;
+                let (key, value) = param
+                    .split_once('=')
+                    .ok_or_else(|| format!("Expected KEY=VALUE, got {param}"))?;
+                args.params.set(key, value);
+            }
+            "--fuzz" => {
+                let seed = raw.next().ok_or("--fuzz needs a seed")?;
+                let seed = seed.parse().map_err(|_| format!("Bad seed: {seed}"))?;
+                args.fuzz = Some(seed);
+            }
+            "--cases" => {
+                let cases = raw.next().ok_or("--cases needs a value")?;
+                args.cases = cases.parse().map_err(|_| format!("Bad count: {cases}"))?;
+            }
+            "--trace" => {
+                let level = raw.next().ok_or("--trace needs a level")?;
+                args.trace = Some(level.parse()?);
+            }
+            "--trace-file" => {
+                args.trace_file = Some(raw.next().ok_or("--trace-file needs a path")?);
+            }
+            "--visualize" => {
+                let delay = raw.next().ok_or("--visualize needs a delay")?;
+                let delay = delay.parse().map_err(|_| format!("Bad delay: {delay}"))?;
+                args.visualize = Some(delay);
+            }
+            "-h" | "--help" => return Err(String::new()),
+            day => match day.parse() {
+                Ok(day) => args.days.push(day),
+                Err(_) if !day.starts_with('-') => {
+                    args.tool = Some((day.to_owned(), raw.by_ref().collect()));
+                }
+                Err(_) => return Err(format!("Unexpected argument: {day}")),
+            },
+        }
+    }
+    Ok(args)
+}
+
+fn trace(args: &Args) -> Result<Trace, String> {
+    if let Some(delay) = args.visualize {
+        let delay = Duration::from_millis(delay);
+        return Ok(Trace::new(
+            args.trace.unwrap_or(Level::Debug),
+            Visualizer { delay },
+        ));
+    }
+    let level = args.trace.unwrap_or(Level::Info);
+    Ok(match &args.trace_file {
+        Some(path) => {
+            let file =
+                std::fs::File::create(path).map_err(|e| format!("Can't create {path}: {e}"))?;
+            Trace::new(level, Writer(std::io::BufWriter::new(file)))
+        }
+        None if args.trace.is_some() => Trace::new(level, Writer(std::io::stderr())),
+        None => Trace::default(),
+    })
+}
+
+fn run(solution: &Solution, input: &str, ctx: &Ctx, example: Option<&str>) -> String {
+    let start = Instant::now();
+    let answer = (solution.run)(input, ctx);
+    let elapsed = start.elapsed();
+    match example {
+        Some(name) => println!("{solution} ({name}): {answer}\n\ttime: {elapsed:?}"),
+        None => println!("{solution}: {answer}\n\ttime: {elapsed:?}"),
+    }
+    answer
+}
+
+/// Returns instead of calling `exit` so the trace file gets flushed on the way out
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(err) => {
+            if !err.is_empty() {
+                eprintln!("{err}\n");
+            }
+            eprint!("{USAGE}");
+            for year in runner::YEARS {
+                eprintln!("\nTools for {}:", year.year);
+                for tool in year.tools() {
+                    eprintln!("    {} {}", tool.name, tool.usage);
+                }
+            }
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let trace = match trace(&args) {
+        Ok(trace) => trace,
+        Err(err) => {
+            eprintln!("{err}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let Some(year) = runner::year(args.year) else {
+        eprintln!("No solutions for year {}", args.year.unwrap_or_default());
+        return ExitCode::FAILURE;
+    };
+
+    if let Some((name, tool_args)) = &args.tool {
+        let Some(tool) = year.tools().find(|t| t.name == name) else {
+            eprintln!("No tool {name} for {}, see --help", year.year);
+            return ExitCode::FAILURE;
+        };
+        if let Err(err) = (tool.run)(year, tool_args) {
+            eprintln!("{name}: {err}");
+            return ExitCode::FAILURE;
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    let mut days = year.solutions().map(|s| s.day).collect::<Vec<_>>();
+    days.dedup();
+    if !args.days.is_empty() {
+        days.retain(|d| args.days.contains(d));
+    }
+
+    if let Err(err) = year.check_params(&days, &args.params) {
+        eprintln!("{err}");
+        return ExitCode::FAILURE;
+    }
+
+    println!("Advent of code {}", year.year);
+
+    if let Some(seed) = args.fuzz {
+        // panics are reported as part of disagreements
+        std::panic::set_hook(Box::new(|_| {}));
+        let mut failed = false;
+        for f in year.fuzz.iter().filter(|f| days.contains(&f.day)) {
+            match fuzz::differential(year, f, &args.params, seed, args.cases) {
+                Ok(()) => println!("Day {}: {} inputs, no disagreements", f.day, args.cases),
+                Err(err) => {
+                    println!("{err}");
+                    failed = true;
+                }
+            }
+        }
+        return if failed {
+            ExitCode::FAILURE
+        } else {
+            ExitCode::SUCCESS
+        };
+    }
+
+    let mut failed = 0;
+    for day in days {
+        let solutions = year.solutions().filter(|s| s.day == day);
+        if args.example {
+            // a typo in a header would quietly run with the default instead
+            let examples = year
+                .examples(day)
+                .into_iter()
+                .filter(|ex| match year.check_params(&[day], &ex.params) {
+                    Ok(()) => true,
+                    Err(err) => {
+                        println!("{}: {err}", ex.name);
+                        failed += 1;
+                        false
+                    }
+                })
+                .collect::<Vec<_>>();
+            for s in solutions {
+                for ex in &examples {
+                    let Some(expected) = ex.answer(s.part) else {
+                        continue;
+                    };
+                    let mut params = ex.params.clone();
+                    params.extend(&args.params);
+                    let ctx = Ctx {
+                        params,
+                        trace: trace.clone(),
+                    };
+                    if run(s, &ex.input, &ctx, Some(&ex.name)) != expected {
+                        println!("\texpected: {expected}");
+                        failed += 1;
+                    }
+                }
+            }
+        } else {
+            let input = year.input(day);
+            let ctx = Ctx {
+                params: args.params.clone(),
+                trace: trace.clone(),
+            };
+            for s in solutions {
+                run(s, &input, &ctx, None);
+            }
+        }
+    }
+
+    if failed > 0 {
+        eprintln!("{failed} example(s) failed");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,438 @@
+//! Solution registry and example fixtures
+//!
+//! Every day exposes a `SOLUTIONS` table, every year collects them into a [`Year`] and runner
+//! picks those up from here. Inputs live in `input/<year>/dayN.txt`, examples live in
+//! `input/<year>/examples/` as plain text files with a small header on top:
+//!
+//! ```text
+//! part1: 143
+//! part2: 123
+//! ---
+//! 47|53
+//! ...
+//! ```
+//!
+//! Header lines are `key: value` pairs, `partN` keys hold expected answers, anything else is a
+//! puzzle parameter, see [`Parameters`].
+
+use std::path::PathBuf;
+
+use crate::{fuzz::Fuzz, trace::Trace};
+
+/// A single solver for a day and a part. There can be several for the same part, they are told
+/// apart by names.
+pub struct Solution {
+    pub day: u32,
+    pub part: u32,
+    pub name: Option<&'static str>,
+    pub run: fn(&str, &Ctx) -> String,
+}
+
+impl Solution {
+    pub const fn new(day: u32, part: u32, run: fn(&str, &Ctx) -> String) -> Self {
+        Self {
+            day,
+            part,
+            name: None,
+            run,
+        }
+    }
+
+    pub const fn named(self, name: &'static str) -> Self {
+        Self {
+            name: Some(name),
+            ..self
+        }
+    }
+}
+
+impl std::fmt::Display for Solution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Day {} - Part {}", self.day, self.part)?;
+        if let Some(name) = self.name {
+            write!(f, " - {name}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Extra command for poking at a puzzle outside of solving it, `aoc TOOL [ARG]...`
+pub struct Tool {
+    pub name: &'static str,
+    /// Arguments and a short description for the help
+    pub usage: &'static str,
+    pub run: fn(&Year, &[String]) -> Result<(), String>,
+}
+
+/// All the solutions for a single year, each year lives in its own `yYYYY` module
+pub struct Year {
+    pub year: u32,
+    pub days: &'static [&'static [Solution]],
+    /// Days with several solutions that must agree, see [`crate::fuzz`]
+    pub fuzz: &'static [Fuzz],
+    pub tools: &'static [&'static [Tool]],
+    /// Days that take [`Parameters`], what they declare
+    pub params: &'static [(u32, Declared)],
+}
+
+pub const YEARS: &[Year] = &[crate::y2024::YEAR];
+
+/// Solutions for a given year, latest one if not specified
+pub fn year(year: Option<u32>) -> Option<&'static Year> {
+    match year {
+        Some(year) => YEARS.iter().find(|y| y.year == year),
+        None => YEARS.iter().max_by_key(|y| y.year),
+    }
+}
+
+/// Puzzle parameters that differ between examples and real inputs: grid sizes, number of steps,
+/// etc. Use [`params!`] to declare them.
+pub trait Parameters: Default {
+    /// Names of all the parameters
+    const KEYS: &'static [&'static str];
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String>;
+}
+
+/// Parameters of a single day, to check values from the command line before running anything
+#[derive(Clone, Copy)]
+pub struct Declared {
+    pub keys: &'static [&'static str],
+    /// Parse the values of the keys this day has
+    pub check: fn(&Overrides) -> Result<(), String>,
+}
+
+/// Declares `Params` struct for a day, fields are given as `name: type = default`. Also
+/// declares `PARAMS` for [`Year::params`].
+macro_rules! params {
+    ($($(#[$meta:meta])* $name:ident: $ty:ty = $default:expr),* $(,)?) => {
+        #[derive(Debug, Clone, Copy)]
+        pub(crate) struct Params {
+            $($(#[$meta])* pub $name: $ty,)*
+        }
+
+        impl Default for Params {
+            fn default() -> Self {
+                Self {
+                    $($name: $default,)*
+                }
+            }
+        }
+
+        impl $crate::runner::Parameters for Params {
+            const KEYS: &'static [&'static str] = &[$(stringify!($name)),*];
+
+            fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+                match key {
+                    $(stringify!($name) => {
+                        self.$name = value.parse().map_err(|e| format!("{key}: {e}"))?
+                    })*
+                    _ => return Err(format!("unknown parameter {key}")),
+                }
+                Ok(())
+            }
+        }
+
+        #[allow(dead_code)]
+        pub(crate) const PARAMS: $crate::runner::Declared = $crate::runner::Declared {
+            keys: <Params as $crate::runner::Parameters>::KEYS,
+            check: |o| o.try_get::<Params>().map(drop),
+        };
+    };
+}
+pub(crate) use params;
+
+/// Parameter values as given in example headers or on the command line
+#[derive(Debug, Clone, Default)]
+pub struct Overrides(Vec<(String, String)>);
+
+impl Overrides {
+    /// Later values take priority over earlier ones
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.0.push((key.to_owned(), value.to_owned()));
+    }
+
+    pub fn extend(&mut self, other: &Overrides) {
+        self.0.extend(other.0.iter().cloned());
+    }
+
+    /// Pull `--param KEY=VALUE` pairs out of tool arguments, the rest of them is returned as is
+    pub fn from_args(args: &[String]) -> Result<(Self, Vec<String>), String> {
+        let mut params = Self::default();
+        let mut rest = Vec::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg != "--param" {
+                rest.push(arg.clone());
+                continue;
+            }
+            let param = args.next().ok_or("--param needs a value")?;
+            let (key, value) = param
+                .split_once('=')
+                .ok_or_else(|| format!("Expected KEY=VALUE, got {param}"))?;
+            params.set(key, value);
+        }
+        Ok((params, rest))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.0.iter().map(|(key, _)| key.as_str())
+    }
+
+    /// Parameters with the values given for them, keys `P` doesn't have are for other days and
+    /// are skipped
+    pub fn try_get<P: Parameters>(&self) -> Result<P, String> {
+        let mut params = P::default();
+        for (key, value) in &self.0 {
+            if P::KEYS.contains(&key.as_str()) {
+                params.set(key, value)?;
+            }
+        }
+        Ok(params)
+    }
+
+    /// Same as [`Overrides::try_get`], values are checked before solvers run so a bad one is a
+    /// bug
+    pub fn get<P: Parameters>(&self) -> P {
+        self.try_get()
+            .unwrap_or_else(|err| panic!("Bad parameter: {err}"))
+    }
+}
+
+/// Everything a solver gets besides the input
+#[derive(Debug, Clone, Default)]
+pub struct Ctx {
+    pub params: Overrides,
+    pub trace: Trace,
+}
+
+impl Ctx {
+    pub fn new(params: Overrides) -> Self {
+        Self {
+            params,
+            trace: Trace::default(),
+        }
+    }
+
+    /// Puzzle parameters for the day, see [`Overrides::get`]
+    pub fn get<P: Parameters>(&self) -> P {
+        self.params.get()
+    }
+}
+
+impl Year {
+    pub fn solutions(&self) -> impl Iterator<Item = &'static Solution> {
+        self.days.iter().copied().flatten()
+    }
+
+    pub fn tools(&self) -> impl Iterator<Item = &'static Tool> {
+        self.tools.iter().copied().flatten()
+    }
+
+    /// Every key must belong to one of the days and parse for all the days that have it
+    pub fn check_params(&self, days: &[u32], params: &Overrides) -> Result<(), String> {
+        let declared = self
+            .params
+            .iter()
+            .filter(|(day, _)| days.contains(day))
+            .collect::<Vec<_>>();
+        if let Some(key) = params
+            .keys()
+            .find(|key| !declared.iter().any(|(_, d)| d.keys.contains(key)))
+        {
+            return Err(format!("None of the days have a parameter {key}"));
+        }
+        for (day, d) in declared {
+            (d.check)(params).map_err(|err| format!("Day {day}: {err}"))?;
+        }
+        Ok(())
+    }
+
+    fn dir(&self) -> PathBuf {
+        [env!("CARGO_MANIFEST_DIR"), "input", &self.year.to_string()]
+            .iter()
+            .collect()
+    }
+
+    /// Real puzzle input for a day
+    pub fn input(&self, day: u32) -> String {
+        let path = self.dir().join(format!("day{day}.txt"));
+        let input = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Can't read {}: {e}", path.display()));
+        input.trim_end_matches('\n').to_owned()
+    }
+
+    /// Input for a tool along with parameters from its header: a file from the command line
+    /// with any example header skipped, real input for the day if there's none. Header
+    /// parameters must belong to the day.
+    pub fn tool_input(&self, day: u32, path: Option<&str>) -> Result<(String, Overrides), String> {
+        let Some(path) = path else {
+            return Ok((self.input(day), Overrides::default()));
+        };
+        let file = std::fs::read_to_string(path).map_err(|e| format!("Can't read {path}: {e}"))?;
+        Ok(match file.split_once("---\n") {
+            Some((header, input)) => {
+                let (_, params) = parse_header(header)?;
+                self.check_params(&[day], &params)
+                    .map_err(|err| format!("{path}: {err}"))?;
+                (input.to_owned(), params)
+            }
+            None => (file, Overrides::default()),
+        })
+    }
+
+    pub fn example(&self, name: &str) -> Example {
+        let path = self.dir().join("examples").join(format!("{name}.txt"));
+        let file = std::fs::read_to_string(&path)
+            .unwrap_or_else(|e| panic!("Can't read {}: {e}", path.display()));
+        Example::parse(name, &file)
+    }
+
+    /// All the examples for a given day, sorted by name
+    pub fn examples(&self, day: u32) -> Vec<Example> {
+        let prefix = format!("day{day}");
+        let mut names = std::fs::read_dir(self.dir().join("examples"))
+            .into_iter()
+            .flatten()
+            .filter_map(|e| {
+                let name = e.ok()?.file_name().into_string().ok()?;
+                let name = name.strip_suffix(".txt")?;
+                let rest = name.strip_prefix(&prefix)?;
+                (rest.is_empty() || rest.starts_with('-')).then(|| name.to_owned())
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+        names.iter().map(|n| self.example(n)).collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Example {
+    /// File name without extension: `day10`, `day10-small`, etc.
+    pub name: String,
+    pub day: u32,
+    /// Expected answers, as `(part, answer)`
+    pub answers: Vec<(u32, String)>,
+    pub params: Overrides,
+    pub input: String,
+}
+
+impl Example {
+    pub fn parse(name: &str, file: &str) -> Self {
+        let day = name
+            .strip_prefix("day")
+            .and_then(|d| d.split('-').next())
+            .and_then(|d| d.parse().ok())
+            .unwrap_or_else(|| panic!("{name}: example name must start with dayN"));
+        let (header, input) = file
+            .split_once("---\n")
+            .unwrap_or_else(|| panic!("{name}: header must be terminated with ---"));
+
+        let (answers, params) = parse_header(header).unwrap_or_else(|err| panic!("{name}: {err}"));
+
+        Self {
+            name: name.to_owned(),
+            day,
+            answers,
+            params,
+            input: input.trim_end_matches('\n').to_owned(),
+        }
+    }
+
+    pub fn answer(&self, part: u32) -> Option<&str> {
+        self.answers
+            .iter()
+            .find_map(|(p, a)| (*p == part).then_some(a.as_str()))
+    }
+}
+
+/// Expected answers and parameters from an example header
+fn parse_header(header: &str) -> Result<(Vec<(u32, String)>, Overrides), String> {
+    let mut answers = Vec::new();
+    let mut params = Overrides::default();
+    for line in header.lines() {
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| format!("{line:?} is not a key: value pair"))?;
+        let key = key.trim();
+        match key.strip_prefix("part").map(str::parse) {
+            Some(Ok(part)) => answers.push((part, value.trim().to_owned())),
+            _ => params.set(key, value.trim()),
+        }
+    }
+    Ok((answers, params))
+}
+
+/// Run every solver registered for this part against the example and compare with the expected
+/// answer from the header
+#[cfg(test)]
+fn check_example(year: u32, name: &str, part: u32) {
+    let year = self::year(Some(year)).unwrap();
+    let example = year.example(name);
+    let expected = example.answer(part).unwrap();
+    year.check_params(&[example.day], &example.params)
+        .unwrap_or_else(|err| panic!("{name}: {err}"));
+    let ctx = Ctx::new(example.params.clone());
+    let mut checked = 0;
+    for s in year
+        .solutions()
+        .filter(|s| s.day == example.day && s.part == part)
+    {
+        let answer = (s.run)(&example.input, &ctx);
+        assert_eq!(answer, expected, "{s} on {name}");
+        checked += 1;
+    }
+    assert!(checked > 0, "No solutions for {name}, part {part}");
+}
+
+#[cfg(test)]
+mod examples {
+    include!(concat!(env!("OUT_DIR"), "/examples.rs"));
+}
+
+#[test]
+fn example_header_works() {
+    let ex = Example::parse("day3-part2", "part2: 48\nsize: 7\n---\nmul(6,8)\n");
+    assert_eq!(ex.day, 3);
+    assert_eq!(ex.params.0, [("size".to_owned(), "7".to_owned())]);
+    assert_eq!(ex.answer(1), None);
+    assert_eq!(ex.answer(2), Some("48"));
+    assert_eq!(ex.input, "mul(6,8)");
+}
+
+#[test]
+fn params_work() {
+    params! {
+        size: usize = 71,
+        cutoff: i32 = 100,
+    }
+    let mut overrides = Overrides::default();
+    assert_eq!(overrides.get::<Params>().size, 71);
+    overrides.set("size", "7");
+    overrides.set("size", "8");
+    let params = overrides.get::<Params>();
+    assert_eq!((params.size, params.cutoff), (8, 100));
+    assert!(Params::default().set("sise", "7").is_err());
+    assert!(Params::default().set("size", "x").is_err());
+
+    // keys of other days are left alone, bad values are errors
+    overrides.set("blinks", "x");
+    assert_eq!(overrides.try_get::<Params>().unwrap().size, 8);
+    assert_eq!(PARAMS.keys, ["size", "cutoff"]);
+    overrides.set("cutoff", "many");
+    assert!((PARAMS.check)(&overrides).is_err());
+}
+
+#[test]
+fn check_params_works() {
+    let year = &crate::y2024::YEAR;
+    let mut params = Overrides::default();
+    params.set("check_moves", "5");
+    assert_eq!(year.check_params(&[8, 15], &params), Ok(()));
+    assert!(year.check_params(&[8], &params).is_err());
+    params.set("size", "x");
+    assert_eq!(
+        year.check_params(&[8, 15], &params),
+        Err("Day 8: size: invalid digit found in string".to_owned())
+    );
+}
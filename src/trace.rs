@@ -0,0 +1,180 @@
+//! Diagnostics from inside the solvers
+//!
+//! Solvers get a [`Trace`] and report what they are doing through it: robot moves, rendered
+//! grids, register states, search expansions. Events are built lazily, so a disabled trace costs
+//! one comparison per call site and nothing is formatted or allocated:
+//!
+//! ```ignore
+//! trace.emit(Level::Trace, || Event::Move { from, to });
+//! ```
+//!
+//! Where events end up is decided by the [`Sink`]: stderr, a file, or a [`Visualizer`] that
+//! animates frames in the terminal.
+
+use std::{cell::RefCell, fmt, io::Write, rc::Rc, str::FromStr, time::Duration};
+
+/// Verbosity of an event, a trace set to some level accepts that level and everything above it
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Level {
+    /// A handful of events per run: final frames, program listings
+    Info,
+    /// An event per step of the outer loop
+    Debug,
+    /// Everything, including the inner loops
+    Trace,
+}
+
+impl FromStr for Level {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "info" => Ok(Level::Info),
+            "debug" => Ok(Level::Debug),
+            "trace" => Ok(Level::Trace),
+            _ => Err(format!(
+                "Unknown trace level {s}, expected info, debug or trace"
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Info => write!(f, "info"),
+            Level::Debug => write!(f, "debug"),
+            Level::Trace => write!(f, "trace"),
+        }
+    }
+}
+
+pub enum Event<'a> {
+    /// Free form text
+    Note(String),
+    /// Something moved on a grid, coordinates are `(x, y)`
+    Move { from: (i32, i32), to: (i32, i32) },
+    /// State of a grid after some number of steps
+    Frame {
+        step: usize,
+        grid: &'a dyn fmt::Debug,
+    },
+    /// Registers of a machine before executing the instruction at byte address `ip`
+    Registers {
+        ip: usize,
+        a: usize,
+        b: usize,
+        c: usize,
+    },
+    /// A search picked up a state to look at
+    Expand {
+        state: &'a dyn fmt::Debug,
+        cost: usize,
+    },
+}
+
+impl fmt::Display for Event<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Event::Note(note) => write!(f, "{note}"),
+            Event::Move { from, to } => write!(f, "move {from:?} -> {to:?}"),
+            Event::Frame { step, grid } => write!(f, "frame {step}:{grid:?}"),
+            Event::Registers { ip, a, b, c } => write!(f, "ip={ip} a={a} b={b} c={c}"),
+            Event::Expand { state, cost } => write!(f, "expand {state:?} at {cost}"),
+        }
+    }
+}
+
+/// Where the events go
+pub trait Sink {
+    fn event(&mut self, level: Level, event: &Event);
+}
+
+/// Writes every event as text, one per line: `[level] event`
+pub struct Writer<W>(pub W);
+
+impl<W: Write> Sink for Writer<W> {
+    fn event(&mut self, level: Level, event: &Event) {
+        // diagnostics are not worth failing the solver over
+        let _ = writeln!(self.0, "[{level}] {event}");
+    }
+}
+
+/// Redraws frames in place on stderr with a pause between them, everything else is ignored
+pub struct Visualizer {
+    pub delay: Duration,
+}
+
+impl Sink for Visualizer {
+    fn event(&mut self, _level: Level, event: &Event) {
+        if let Event::Frame { step, grid } = event {
+            eprint!("\x1b[2J\x1b[H{step}:{grid:?}");
+            std::thread::sleep(self.delay);
+        }
+    }
+}
+
+/// Handle solvers report events to, cheap to clone. Default one is disabled.
+#[derive(Clone, Default)]
+pub struct Trace(Option<(Level, Rc<RefCell<dyn Sink>>)>);
+
+impl Trace {
+    pub fn new(level: Level, sink: impl Sink + 'static) -> Self {
+        Self(Some((level, Rc::new(RefCell::new(sink)))))
+    }
+
+    pub fn enabled(&self, level: Level) -> bool {
+        matches!(self.0, Some((max, _)) if level <= max)
+    }
+
+    /// Report an event, `event` is only called if the level is enabled
+    #[inline]
+    pub fn emit<'a>(&self, level: Level, event: impl FnOnce() -> Event<'a>) {
+        if let Some((max, sink)) = &self.0 {
+            if level <= *max {
+                sink.borrow_mut().event(level, &event());
+            }
+        }
+    }
+
+    /// Pass on an event that was already built, for sinks that wrap another trace
+    pub fn forward(&self, level: Level, event: &Event) {
+        if let Some((max, sink)) = &self.0 {
+            if level <= *max {
+                sink.borrow_mut().event(level, event);
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            Some((level, _)) => write!(f, "Trace({level})"),
+            None => write!(f, "Trace(off)"),
+        }
+    }
+}
+
+#[test]
+fn trace_filters_by_level() {
+    struct Lines(Rc<RefCell<Vec<String>>>);
+    impl Sink for Lines {
+        fn event(&mut self, level: Level, event: &Event) {
+            self.0.borrow_mut().push(format!("{level} {event}"));
+        }
+    }
+
+    let lines = Rc::new(RefCell::new(Vec::new()));
+    let trace = Trace::new(Level::Debug, Lines(lines.clone()));
+    trace.emit(Level::Info, || Event::Note("hi".to_owned()));
+    trace.emit(Level::Debug, || Event::Move {
+        from: (0, 0),
+        to: (1, 0),
+    });
+    trace.emit(Level::Trace, || unreachable!());
+    assert_eq!(*lines.borrow(), ["info hi", "debug move (0, 0) -> (1, 0)"]);
+
+    Trace::default().emit(Level::Info, || unreachable!());
+    assert!(!Trace::default().enabled(Level::Info));
+}
@@ -1,8 +1,12 @@
 use std::collections::HashMap;
 
-use aoc_runner_derive::{aoc, aoc_generator};
+use crate::runner::Solution;
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(1, 1, |i, _| part1(&parse(i)).to_string()),
+    Solution::new(1, 2, |i, _| part2(&parse(i)).to_string()),
+];
 
-#[aoc_generator(day1)]
 pub fn parse(input: &str) -> Vec<(u32, u32)> {
     input
         .lines()
@@ -16,17 +20,13 @@ pub fn parse(input: &str) -> Vec<(u32, u32)> {
         .collect()
 }
 
-#[aoc(day1, part1)]
 pub fn part1(input: &[(u32, u32)]) -> u32 {
     let (mut a, mut b): (Vec<u32>, Vec<u32>) = input.iter().copied().unzip();
     a.sort();
     b.sort();
-    std::iter::zip(a, b)
-        .map(|(a, b)| if a > b { a - b } else { b - a })
-        .sum()
+    std::iter::zip(a, b).map(|(a, b)| a.abs_diff(b)).sum()
 }
 
-#[aoc(day1, part2)]
 fn part2(input: &[(u32, u32)]) -> u32 {
     let mut m = HashMap::<_, u32>::new();
     for (_, i) in input {
@@ -1,6 +1,10 @@
-use aoc_runner_derive::{aoc, aoc_generator};
+use crate::runner::Solution;
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(2, 1, |i, _| part1(&parse(i)).to_string()),
+    Solution::new(2, 2, |i, _| part2(&parse(i)).to_string()),
+];
 
-#[aoc_generator(day2)]
 pub fn parse(input: &str) -> Vec<Vec<u32>> {
     input
         .lines()
@@ -12,12 +16,10 @@ pub fn parse(input: &str) -> Vec<Vec<u32>> {
         .collect()
 }
 
-#[aoc(day2, part1)]
 pub fn part1(input: &[Vec<u32>]) -> usize {
     input.iter().filter(|i| safe1(i)).count()
 }
 
-#[aoc(day2, part2)]
 pub fn part2(input: &[Vec<u32>]) -> usize {
     input.iter().filter(|i| safe2(i)).count()
 }
@@ -26,7 +28,7 @@ fn safe1(input: &[u32]) -> bool {
     let dir = input[0].cmp(&input[1]);
     input.windows(2).all(|w| {
         let (a, b) = (w[0], w[1]);
-        let diff = if a > b { a - b } else { b - a };
+        let diff = a.abs_diff(b);
         // The levels are either all increasing or all decreasing.
         // Any two adjacent levels differ by at least one and at most three.
         a.cmp(&b) == dir && (1..=3).contains(&diff)
@@ -1,7 +1,12 @@
-use aoc_runner_derive::{aoc, aoc_generator};
 use regex::Regex;
 
-#[aoc_generator(day3, part1)]
+use crate::runner::Solution;
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(3, 1, |i, _| part1(&parse1(i)).to_string()),
+    Solution::new(3, 2, |i, _| part2(&parse2(i)).to_string()),
+];
+
 fn parse1(input: &str) -> Vec<(u32, u32)> {
     let mut res = Vec::new();
     // this gets compiled several times, but it is small so don't care :)
@@ -16,7 +21,6 @@ fn dec(input: &str) -> u32 {
     input.parse::<u32>().unwrap()
 }
 
-#[aoc_generator(day3, part2)]
 fn parse2(input: &str) -> Vec<(u32, u32)> {
     let mut res = Vec::new();
     let mut enabled = true;
@@ -37,25 +41,10 @@ fn parse2(input: &str) -> Vec<(u32, u32)> {
     res
 }
 
-#[aoc(day3, part1)]
 fn part1(input: &[(u32, u32)]) -> u32 {
     input.iter().map(|(a, b)| a * b).sum()
 }
 
-#[aoc(day3, part2)]
 fn part2(input: &[(u32, u32)]) -> u32 {
     input.iter().map(|(a, b)| a * b).sum()
 }
-
-#[test]
-fn parse1_works() {
-    let input = "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))";
-    assert_eq!(161, part1(&parse1(input)));
-}
-
-#[test]
-fn parse2_works() {
-    // grrrrrrrrr. Different example for part 2, shame on whoever. Wasted a bunch of time.
-    let input = "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))";
-    assert_eq!(48, part2(&parse2(input)));
-}
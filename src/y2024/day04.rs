@@ -1,9 +1,13 @@
 use std::collections::HashMap;
 
-use aoc_runner_derive::{aoc, aoc_generator};
+use crate::runner::Solution;
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(4, 1, |i, _| part1(&parse(i)).to_string()),
+    Solution::new(4, 2, |i, _| part2(&parse(i)).to_string()),
+];
 
 // Vec<Vec
-#[aoc_generator(day4)]
 fn parse(input: &str) -> HashMap<(i32, i32), char> {
     let mut res = HashMap::new();
     for (y, line) in input.lines().enumerate() {
@@ -14,7 +18,6 @@ fn parse(input: &str) -> HashMap<(i32, i32), char> {
     res
 }
 
-#[aoc(day4, part1)]
 fn part1(input: &HashMap<(i32, i32), char>) -> u32 {
     let mut count = 0;
 
@@ -50,7 +53,6 @@ fn part1(input: &HashMap<(i32, i32), char>) -> u32 {
     count
 }
 
-#[aoc(day4, part2)]
 fn part2(input: &HashMap<(i32, i32), char>) -> u32 {
     let get = |x: i32, y: i32| input.get(&(x, y)).copied().unwrap_or('?');
     let mut count = 0;
@@ -95,33 +97,3 @@ fn part2(input: &HashMap<(i32, i32), char>) -> u32 {
     // not reaching here
     count
 }
-
-#[test]
-fn part1_test() {
-    let input = "MMMSXXMASM
-MSAMXMSMSA
-AMXSXMAAMM
-MSAMASMSMX
-XMASAMXAMM
-XXAMMXXAMA
-SMSMSASXSS
-SAXAMASAAA
-MAMMMXMMMM
-MXMXAXMASX";
-    assert_eq!(part1(&parse(input)), 18);
-}
-
-#[test]
-fn part2_test() {
-    let input = "MMMSXXMASM
-MSAMXMSMSA
-AMXSXMAAMM
-MSAMASMSMX
-XMASAMXAMM
-XXAMMXXAMA
-SMSMSASXSS
-SAXAMASAAA
-MAMMMXMMMM
-MXMXAXMASX";
-    assert_eq!(part2(&parse(input)), 9);
-}
@@ -1,9 +1,13 @@
-use aoc_runner_derive::{aoc, aoc_generator};
+use crate::runner::Solution;
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(5, 1, |i, _| part1(&parse(i)).to_string()),
+    Solution::new(5, 2, |i, _| part2(&parse(i)).to_string()),
+];
 
 type Rules = Vec<(u32, u32)>;
 type Sets = Vec<Vec<u32>>;
 
-#[aoc_generator(day5)]
 fn parse(input: &str) -> (Rules, Sets) {
     let (ordering, sets) = input.split_once("\n\n").unwrap();
     let ordering = ordering
@@ -25,7 +29,6 @@ fn parse(input: &str) -> (Rules, Sets) {
     (ordering, sets)
 }
 
-#[aoc(day5, part1)]
 fn part1(input: &(Rules, Sets)) -> u32 {
     let mut res = 0;
     for set in input.1.iter() {
@@ -36,7 +39,6 @@ fn part1(input: &(Rules, Sets)) -> u32 {
     res
 }
 
-#[aoc(day5, part2)]
 fn part2(input: &(Rules, Sets)) -> u32 {
     let mut res = 0;
     for set in input.1.iter() {
@@ -73,69 +75,3 @@ fn is_valid_sort(input: &[u32], rules: &[(u32, u32)]) -> bool {
     }
     true
 }
-
-#[test]
-fn part1w() {
-    let input = "47|53
-97|13
-97|61
-97|47
-75|29
-61|13
-75|53
-29|13
-97|29
-53|29
-61|53
-97|53
-61|29
-47|13
-75|47
-97|75
-47|61
-75|61
-47|29
-75|13
-53|13
-
-75,47,61,53,29
-97,61,53,29,13
-75,29,13
-75,97,47,61,53
-61,13,29
-97,13,75,29,47";
-    assert_eq!(part1(&parse(input)), 143);
-}
-
-#[test]
-fn part2w() {
-    let input = "47|53
-97|13
-97|61
-97|47
-75|29
-61|13
-75|53
-29|13
-97|29
-53|29
-61|53
-97|53
-61|29
-47|13
-75|47
-97|75
-47|61
-75|61
-47|29
-75|13
-53|13
-
-75,47,61,53,29
-97,61,53,29,13
-75,29,13
-75,97,47,61,53
-61,13,29
-97,13,75,29,47";
-    assert_eq!(part2(&parse(input)), 123);
-}
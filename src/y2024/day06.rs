@@ -1,8 +1,11 @@
 use std::{cell::Cell, collections::HashSet, rc::Rc};
 
-use aoc_runner_derive::{aoc, aoc_generator};
+use crate::{runner::Solution, TwoDee};
 
-use crate::TwoDee;
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(6, 1, |i, _| part1(&parse(i)).to_string()),
+    Solution::new(6, 2, |i, _| part2(&parse(i)).to_string()),
+];
 
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
 struct Guard {
@@ -34,7 +37,6 @@ impl Guard {
         self.y = self.y.wrapping_add_signed(self.dy);
     }
 }
-#[aoc_generator(day6)]
 fn parse(input: &str) -> (Guard, TwoDee<bool>) {
     let guard = Rc::new(Cell::new(None));
     let gg = guard.clone();
@@ -62,17 +64,13 @@ fn parse(input: &str) -> (Guard, TwoDee<bool>) {
     (guard.take().unwrap(), twodee)
 }
 
-#[aoc(day6, part1)]
 fn part1(input: &(Guard, TwoDee<bool>)) -> usize {
     let (guard_start, room) = input;
     let mut room: TwoDee<bool> = room.clone();
     let mut visited = HashSet::new();
     let mut guard = *guard_start;
     visited.insert((guard.x, guard.y)); // Evil example
-    loop {
-        let Some(wall) = room.get(guard.peek()).copied() else {
-            break;
-        };
+    while let Some(wall) = room.get(guard.peek()).copied() {
         room.poi = (guard.x, guard.y);
 
         if wall {
@@ -85,7 +83,6 @@ fn part1(input: &(Guard, TwoDee<bool>)) -> usize {
     visited.len()
 }
 
-#[aoc(day6, part2)]
 fn part2(input: &(Guard, TwoDee<bool>)) -> usize {
     let (guard_start, room) = input;
     let mut room: TwoDee<bool> = room.clone();
@@ -95,10 +92,7 @@ fn part2(input: &(Guard, TwoDee<bool>)) -> usize {
 
     // first we see here guard visits - adding an obstacle on a cell that was never visited - waste
     // of time
-    loop {
-        let Some(wall) = room.get(guard.peek()).copied() else {
-            break;
-        };
+    while let Some(wall) = room.get(guard.peek()).copied() {
         room.poi = (guard.x, guard.y);
 
         if wall {
@@ -141,33 +135,3 @@ fn part2(input: &(Guard, TwoDee<bool>)) -> usize {
 
     res
 }
-
-#[test]
-fn part1w() {
-    let input = "....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#...";
-    assert_eq!(41, part1(&parse(input)));
-}
-
-#[test]
-fn part2w() {
-    let input = "....#.....
-.........#
-..........
-..#.......
-.......#..
-..........
-.#..^.....
-........#.
-#.........
-......#...";
-    assert_eq!(6, part2(&parse(input)));
-}
@@ -1,6 +1,10 @@
-use aoc_runner_derive::{aoc, aoc_generator};
+use crate::runner::Solution;
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(7, 1, |i, _| part1(&parse(i)).to_string()),
+    Solution::new(7, 2, |i, _| part2(&parse(i)).to_string()),
+];
 
-#[aoc_generator(day7)]
 fn parse(input: &str) -> Vec<Vec<u64>> {
     input
         .lines()
@@ -12,7 +16,6 @@ fn parse(input: &str) -> Vec<Vec<u64>> {
         .collect()
 }
 
-#[aoc(day7, part1)]
 fn part1(input: &[Vec<u64>]) -> u64 {
     input
         .iter()
@@ -20,7 +23,6 @@ fn part1(input: &[Vec<u64>]) -> u64 {
         .sum()
 }
 
-#[aoc(day7, part2)]
 fn part2(input: &[Vec<u64>]) -> u64 {
     input
         .iter()
@@ -77,18 +79,3 @@ fn can_be_solved2(input: &[u64]) -> bool {
     }
     cur.contains(&input[0])
 }
-
-#[test]
-fn part1w() {
-    let input = "190: 10 19
-3267: 81 40 27
-83: 17 5
-156: 15 6
-7290: 6 8 6 15
-161011: 16 10 13
-192: 17 8 14
-21037: 9 7 18 13
-292: 11 6 16 20";
-
-    assert_eq!(part1(&parse(input)), 3749);
-}
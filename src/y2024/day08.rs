@@ -1,8 +1,19 @@
 use std::collections::HashSet;
 
-use aoc_runner_derive::{aoc, aoc_generator};
-
-use crate::Point;
+use crate::{
+    runner::{params, Solution},
+    Point,
+};
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(8, 1, |i, p| part1(&parse(i), &p.get()).to_string()),
+    Solution::new(8, 2, |i, p| part2(&parse(i), &p.get()).to_string()),
+];
+
+params! {
+    /// Width and height of the map
+    size: usize = 50,
+}
 
 #[derive(Debug, Copy, Clone)]
 struct Ant {
@@ -11,7 +22,6 @@ struct Ant {
     freq: char,
 }
 
-#[aoc_generator(day8)]
 fn parse(input: &str) -> Vec<Ant> {
     let mut res = Vec::new();
     for (y, line) in input.lines().enumerate() {
@@ -37,10 +47,8 @@ impl From<Ant> for Point {
     }
 }
 
-#[aoc(day8, part1)]
-fn part1(input: &[Ant]) -> usize {
-    // Can't be arsed to pass it as well
-    let dim = if input.len() == 7 { 12 } else { 50 };
+fn part1(input: &[Ant], params: &Params) -> usize {
+    let dim = params.size;
 
     let mut out = HashSet::<Point>::new();
     for i in 0..input.len() - 1 {
@@ -66,10 +74,8 @@ fn part1(input: &[Ant]) -> usize {
     out.len()
 }
 
-#[aoc(day8, part2)]
-fn part2(input: &[Ant]) -> usize {
-    // Can't be arsed to pass it as well
-    let dim = if input.len() == 7 { 12 } else { 50 };
+fn part2(input: &[Ant], params: &Params) -> usize {
+    let dim = params.size;
     let mut cnt = 0;
 
     let mut out = vec![false; dim * dim];
@@ -108,37 +114,3 @@ fn part2(input: &[Ant]) -> usize {
     }
     cnt
 }
-
-#[test]
-fn part1w() {
-    let input = "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............";
-    assert_eq!(14, part1(&parse(input)));
-}
-
-#[test]
-fn part2w() {
-    let input = "............
-........0...
-.....0......
-.......0....
-....0.......
-......A.....
-............
-............
-........A...
-.........A..
-............
-............";
-    assert_eq!(34, part2(&parse(input)));
-}
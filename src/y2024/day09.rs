@@ -1,6 +1,10 @@
-use aoc_runner_derive::{aoc, aoc_generator};
+use crate::runner::Solution;
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(9, 1, |i, _| part1(&parse(i)).to_string()),
+    Solution::new(9, 2, |i, _| part2(&parse(i)).to_string()),
+];
 
-#[aoc_generator(day9)]
 fn parse(input: &str) -> Vec<Option<usize>> {
     let mut file = true;
     let mut id = 0;
@@ -21,7 +25,6 @@ fn parse(input: &str) -> Vec<Option<usize>> {
     res
 }
 
-#[aoc(day9, part1)]
 fn part1(input: &[Option<usize>]) -> usize {
     let mut input = input.to_vec();
 
@@ -42,7 +45,6 @@ fn part1(input: &[Option<usize>]) -> usize {
     checksum(&input)
 }
 
-#[aoc(day9, part2)]
 fn part2(input: &[Option<usize>]) -> usize {
     let mut input = input.to_vec();
     let mut j = input.len() - 1;
@@ -85,15 +87,3 @@ fn checksum(input: &[Option<usize>]) -> usize {
         .map(|(ix, mid)| mid.map_or(0, |id| id * ix))
         .sum()
 }
-
-#[test]
-fn part1w() {
-    let input = "2333133121414131402";
-    assert_eq!(1928, part1(&parse(input)));
-}
-
-#[test]
-fn part2w() {
-    let input = "2333133121414131402";
-    assert_eq!(2858, part2(&parse(input)));
-}
@@ -1,9 +1,25 @@
 use std::collections::{HashMap, HashSet};
 
-use crate::{Point, TwoDee};
-use aoc_runner_derive::{aoc, aoc_generator};
+use crate::{
+    fuzz::{Fuzz, Rng},
+    runner::Solution,
+    Point, TwoDee,
+};
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(10, 1, |i, _| part1(&parse(i)).to_string()),
+    Solution::new(10, 1, |i, _| part1bfs(&parse(i)).to_string()).named("bfs"),
+    Solution::new(10, 2, |i, _| part2(&parse(i)).to_string()),
+    Solution::new(10, 2, |i, _| part2_b(&parse(i)).to_string()).named("bfs"),
+];
+
+pub(crate) const FUZZ: Fuzz = Fuzz {
+    day: 10,
+    generate,
+    shrink,
+    params: &[],
+};
 
-#[aoc_generator(day10)]
 fn parse(input: &str) -> TwoDee<u8> {
     input
         .lines()
@@ -11,7 +27,6 @@ fn parse(input: &str) -> TwoDee<u8> {
         .collect::<TwoDee<u8>>()
 }
 
-#[aoc(day10, part1)]
 fn part1(input: &TwoDee<u8>) -> usize {
     let accessible = populate(input);
     let mut count = 0;
@@ -25,7 +40,6 @@ fn part1(input: &TwoDee<u8>) -> usize {
     count
 }
 
-#[aoc(day10, part1, bfs)]
 fn part1bfs(input: &TwoDee<u8>) -> usize {
     let accessible = populate_bfs(input);
     let mut count = 0;
@@ -106,7 +120,6 @@ fn populate(input: &TwoDee<u8>) -> TwoDee<HashMap<Point, usize>> {
     }
     accessible
 }
-#[aoc(day10, part2)]
 fn part2(input: &TwoDee<u8>) -> usize {
     let accessible = populate(input);
     let mut count = 0;
@@ -121,7 +134,6 @@ fn part2(input: &TwoDee<u8>) -> usize {
     count
 }
 
-#[aoc(day10, part2, bfs)]
 fn part2_b(input: &TwoDee<u8>) -> usize {
     let accessible = populate_bfs(input);
     let mut count = 0;
@@ -136,47 +148,39 @@ fn part2_b(input: &TwoDee<u8>) -> usize {
     count
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn part1_example() {
-        let input = "0123
-1234
-8765
-9876";
-        assert_eq!(part1(&parse(input)), 1);
-
-        let input = "89010123
-78121874
-87430965
-96549874
-45678903
-32019012
-01329801
-10456732";
-        assert_eq!(part1(&parse(input)), 36);
+/// Random heights with a few trails carved on top
+fn generate(rng: &mut Rng) -> String {
+    let size = rng.range(2..=8);
+    let mut grid = (0..size)
+        .map(|_| (0..size).map(|_| rng.below(10) as u8).collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    for _ in 0..rng.range(1..=4) {
+        let (mut x, mut y) = (rng.below(size), rng.below(size));
+        for h in 0..10 {
+            grid[y][x] = h;
+            let d = rng.pick(&Point::DIRS);
+            if let Some(next) = (Point::new(x, y) + d).guard(size) {
+                (x, y) = (next.x as usize, next.y as usize);
+            }
+        }
     }
+    grid.iter()
+        .map(|row| row.iter().map(|h| (b'0' + h) as char).collect::<String>())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-    #[test]
-    fn part2_example() {
-        let input = "012345
-123456
-234567
-345678
-416789
-567891";
-        assert_eq!(part2(&parse(input)), 227);
-
-        let input = "89010123
-78121874
-87430965
-96549874
-45678903
-32019012
-01329801
-10456732";
-        assert_eq!(part2(&parse(input)), 81);
+/// Map has to stay square, cut a row and a column from either side
+fn shrink(input: &str) -> Vec<String> {
+    let lines = input.lines().collect::<Vec<_>>();
+    if lines.len() < 2 {
+        return Vec::new();
     }
+    let n = lines.len() - 1;
+    let top_left = lines[..n].iter().map(|l| &l[..n]);
+    let bottom_right = lines[1..].iter().map(|l| &l[1..]);
+    vec![
+        top_left.collect::<Vec<_>>().join("\n"),
+        bottom_right.collect::<Vec<_>>().join("\n"),
+    ]
 }
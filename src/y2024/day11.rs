@@ -1,7 +1,28 @@
 use std::collections::HashMap;
 
-use aoc_runner_derive::{aoc, aoc_generator};
-#[aoc_generator(day11)]
+use crate::{
+    fuzz::{self, Fuzz, Rng},
+    runner::{params, Solution},
+};
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(11, 1, |i, p| part1(&parse(i), &p.get()).to_string()).named("brute"),
+    Solution::new(11, 1, |i, p| part1s(&parse(i), &p.get()).to_string()).named("smort"),
+    Solution::new(11, 2, |i, p| part2(&parse(i), &p.get()).to_string()).named("smort"),
+];
+
+pub(crate) const FUZZ: Fuzz = Fuzz {
+    day: 11,
+    generate,
+    shrink: fuzz::shrink_words,
+    params: &[],
+};
+
+params! {
+    blinks1: usize = 25,
+    blinks2: usize = 75,
+}
+
 fn parse(input: &str) -> Vec<usize> {
     input
         .split_whitespace()
@@ -43,26 +64,24 @@ impl IntoIterator for Stones {
     }
 }
 
-#[aoc(day11, part1, brute)]
-fn part1(input: &[usize]) -> usize {
+fn part1(input: &[usize], params: &Params) -> usize {
     let mut input = input.to_vec();
     let mut output = Vec::new();
-    for _ in 0..25 {
+    for _ in 0..params.blinks1 {
         output.extend(input.drain(..).flat_map(rules));
         std::mem::swap(&mut input, &mut output);
     }
     input.len()
 }
 
-#[aoc(day11, part1, smort)]
-fn part1s(input: &[usize]) -> usize {
+fn part1s(input: &[usize], params: &Params) -> usize {
     let mut stones = HashMap::<usize, usize>::new();
     for s in input {
         *stones.entry(*s).or_default() += 1;
     }
 
     let mut output = HashMap::new();
-    for _ in 0..25 {
+    for _ in 0..params.blinks1 {
         for (k, n) in stones.drain() {
             match rules(k) {
                 Stones::One(k) => {
@@ -80,15 +99,14 @@ fn part1s(input: &[usize]) -> usize {
     stones.values().copied().sum()
 }
 
-#[aoc(day11, part2, smort)]
-fn part2(input: &[usize]) -> usize {
+fn part2(input: &[usize], params: &Params) -> usize {
     let mut stones = HashMap::<usize, usize>::new();
     for s in input {
         *stones.entry(*s).or_default() += 1;
     }
 
     let mut output = HashMap::new();
-    for _ in 0..75 {
+    for _ in 0..params.blinks2 {
         for (k, n) in stones.drain() {
             match rules(k) {
                 Stones::One(k) => {
@@ -106,6 +124,20 @@ fn part2(input: &[usize]) -> usize {
     stones.values().copied().sum()
 }
 
+fn generate(rng: &mut Rng) -> String {
+    (0..rng.range(1..=4))
+        .map(|_| {
+            if rng.one_in(2) {
+                rng.below(20)
+            } else {
+                rng.below(1_000_000)
+            }
+            .to_string()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -114,11 +146,4 @@ mod tests {
     fn rules_work() {
         assert_eq!(rules(10), Stones::Two(1, 0));
     }
-
-    #[test]
-    fn part1_example() {
-        part1(&parse("0 1 10 99 999"));
-        assert_eq!(part1(&parse("125 17")), 55312);
-        assert_eq!(part1s(&parse("125 17")), 55312);
-    }
 }
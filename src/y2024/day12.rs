@@ -1,9 +1,12 @@
 use std::collections::{BTreeMap, BTreeSet};
 
-use aoc_runner_derive::{aoc, aoc_generator};
+use crate::{runner::Solution, Point, TwoDee};
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(12, 1, |i, _| part1(&parse(i)).to_string()),
+    Solution::new(12, 2, |i, _| part2(&parse(i)).to_string()),
+];
 
-use crate::{Point, TwoDee};
-#[aoc_generator(day12)]
 fn parse(input: &str) -> TwoDee<u8> {
     input.lines().map(|l| l.bytes()).collect()
 }
@@ -47,7 +50,6 @@ fn uniq_regions(input: &TwoDee<u8>) -> TwoDee<usize> {
     res
 }
 
-#[aoc(day12, part1)]
 fn part1(input: &TwoDee<u8>) -> usize {
     let u = uniq_regions(input);
     let mut area = BTreeMap::<usize, usize>::new(); // can be a vector...
@@ -96,7 +98,6 @@ fn part1(input: &TwoDee<u8>) -> usize {
         .sum()
 }
 
-#[aoc(day12, part2)]
 fn part2(input: &TwoDee<u8>) -> usize {
     let uniq = uniq_regions(input);
     let mut area = BTreeMap::<usize, usize>::new(); // can be a vector...
@@ -145,107 +146,3 @@ fn part2(input: &TwoDee<u8>) -> usize {
         .map(|(a, p)| *a * *p)
         .sum()
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn part1_example() {
-        let input = "\
-AAA
-ABA
-ABA";
-        assert_eq!(part1(&parse(input)), 7 * 16 + 2 * 6);
-
-        let input = "\
-AAA
-ABB
-AAA";
-        assert_eq!(part1(&parse(input)), 7 * 16 + 2 * 6);
-
-        let input = "\
-AAA
-BBA
-AAA";
-        assert_eq!(part1(&parse(input)), 7 * 16 + 2 * 6);
-
-        let input = "\
-ABA
-ABA
-AAA";
-        assert_eq!(part1(&parse(input)), 7 * 16 + 2 * 6);
-
-        let input = "\
-AAAA
-BBCD
-BBCC
-EEEC";
-        assert_eq!(part1(&parse(input)), 140);
-
-        let input = "\
-OOOOO
-OXOXO
-OOOOO
-OXOXO
-OOOOO";
-        assert_eq!(part1(&parse(input)), 772);
-
-        let input = "\
-RRRRIICCFF
-RRRRIICCCF
-VVRRRCCFFF
-VVRCCCJFFF
-VVVVCJJCFE
-VVIVCCJJEE
-VVIIICJJEE
-MIIIIIJJEE
-MIIISIJEEE
-MMMISSJEEE";
-        assert_eq!(part1(&parse(input)), 1930);
-    }
-
-    #[test]
-    fn part2_example() {
-        let input = "\
-AAA
-ABB
-AAA";
-        assert_eq!(part2(&parse(input)), 8 * 7 + 4 * 2);
-
-        let input = "AA\nAA";
-        assert_eq!(part2(&parse(input)), 4 * 4);
-
-        let input = "\
-AAAA
-BBCD
-BBCC
-EEEC";
-        assert_eq!(part2(&parse(input)), 80);
-
-        let input = "\
-OOOOO
-OXOXO
-OOOOO
-OXOXO
-OOOOO";
-        assert_eq!(part2(&parse(input)), 436);
-
-        let input = "\
-EEEEE
-EXXXX
-EEEEE
-EXXXX
-EEEEE";
-        assert_eq!(part2(&parse(input)), 236);
-
-        let input = "\
-AAAAAA
-AAABBA
-AAABBA
-ABBAAA
-ABBAAA
-AAAAAA";
-        assert_eq!(part2(&parse(input)), 368);
-    }
-}
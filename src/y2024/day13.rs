@@ -1,7 +1,23 @@
-use aoc_runner_derive::{aoc, aoc_generator};
 use regex::Regex;
 
-use crate::Point;
+use crate::{
+    fuzz::{self, Fuzz, Rng},
+    runner::Solution,
+    Point,
+};
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(13, 1, |i, _| part1b(&parse(i)).to_string()).named("brute"),
+    Solution::new(13, 1, |i, _| part1s(&parse(i)).to_string()).named("semismort"),
+    Solution::new(13, 2, |i, _| part2(&parse(i)).to_string()),
+];
+
+pub(crate) const FUZZ: Fuzz = Fuzz {
+    day: 13,
+    generate,
+    shrink: fuzz::shrink_blocks,
+    params: &[],
+};
 
 #[derive(Debug, Copy, Clone)]
 struct Problem {
@@ -10,7 +26,6 @@ struct Problem {
     prize: Point,
 }
 
-#[aoc_generator(day13)]
 fn parse(input: &str) -> Vec<Problem> {
     let mut res = Vec::new();
     let reg = Regex::new(
@@ -39,8 +54,8 @@ Prize: X=(\\d+), Y=(\\d+)",
 /// You also estimate that this is not going to work for part 2.
 fn brute(problem: Problem) -> Option<usize> {
     let mut best = None;
-    for a in 0..100usize {
-        for b in 0..100usize {
+    for a in 0..=100usize {
+        for b in 0..=100usize {
             if problem.a * a + problem.b * b == problem.prize {
                 let this = Some(a * 3 + b);
                 if best.is_none() {
@@ -54,7 +69,7 @@ fn brute(problem: Problem) -> Option<usize> {
     best
 }
 
-fn semismort(problem: Problem, adj: usize) -> Option<usize> {
+fn semismort(problem: Problem, adj: usize, max_presses: usize) -> Option<usize> {
     let ax = problem.a.x as usize;
     let ay = problem.a.y as usize;
 
@@ -99,10 +114,14 @@ fn semismort(problem: Problem, adj: usize) -> Option<usize> {
     // v1, v2 is the answer in f64 form
 
     // we got an answer, but in f64 form. Real answer is probably around here.
+    // Round first: adding 1.0 to 31.999999999999996 gives 33.0, not 32.99...
     for dx in [-1.0, 0.0, 1.0] {
         for dy in [-1.0, 0.0, 1.0] {
-            let (a, b) = ((v1 + dx) as usize, (v2 + dy) as usize);
+            let (a, b) = ((v1.round() + dx) as usize, (v2.round() + dy) as usize);
 
+            if a > max_presses || b > max_presses {
+                continue;
+            }
             if ax * a + bx * b == px && ay * a + by * b == py {
                 return Some(a * 3 + b);
             }
@@ -112,69 +131,39 @@ fn semismort(problem: Problem, adj: usize) -> Option<usize> {
     None
 }
 
-#[aoc(day13, part1, brute)]
 fn part1b(input: &[Problem]) -> usize {
     input.iter().filter_map(|p| brute(*p)).sum()
 }
 
-#[aoc(day13, part1, semismort)]
 fn part1s(input: &[Problem]) -> usize {
-    input.iter().filter_map(|p| semismort(*p, 0)).sum()
+    input.iter().filter_map(|p| semismort(*p, 0, 100)).sum()
 }
 
-#[aoc(day13, part2)]
 fn part2(input: &[Problem]) -> usize {
     input
         .iter()
-        .filter_map(|p| semismort(*p, 10_000_000_000_000))
+        .filter_map(|p| semismort(*p, 10_000_000_000_000, usize::MAX))
         .sum()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn part1_example() {
-        let input = "\
-Button A: X+94, Y+34
-Button B: X+22, Y+67
-Prize: X=8400, Y=5400
-
-Button A: X+26, Y+66
-Button B: X+67, Y+21
-Prize: X=12748, Y=12176
-
-Button A: X+17, Y+86
-Button B: X+84, Y+37
-Prize: X=7870, Y=6450
-
-Button A: X+69, Y+23
-Button B: X+27, Y+71
-Prize: X=18641, Y=10279";
-
-        assert_eq!(part1s(&parse(input)), 480);
-    }
-
-    #[test]
-    fn part2_example() {
-        let input = "\
-Button A: X+94, Y+34
-Button B: X+22, Y+67
-Prize: X=8400, Y=5400
-
-Button A: X+26, Y+66
-Button B: X+67, Y+21
-Prize: X=12748, Y=12176
-
-Button A: X+17, Y+86
-Button B: X+84, Y+37
-Prize: X=7870, Y=6450
-
-Button A: X+69, Y+23
-Button B: X+27, Y+71
-Prize: X=18641, Y=10279";
-
-        assert_eq!(part2(&parse(input)), 875318608908);
-    }
+/// Mostly solvable machines, some need a bit more than 100 presses. Buttons are never collinear,
+/// same as in real inputs - semismort relies on that.
+fn generate(rng: &mut Rng) -> String {
+    (0..rng.range(1..=4))
+        .map(|_| {
+            let (ax, ay) = (rng.range(1..=99), rng.range(1..=99));
+            let (mut bx, mut by) = (ax, ay);
+            while ax * by == ay * bx {
+                (bx, by) = (rng.range(1..=99), rng.range(1..=99));
+            }
+            let (a, b) = (rng.range(0..=110), rng.range(0..=110));
+            let (mut px, mut py) = (ax * a + bx * b, ay * a + by * b);
+            if rng.one_in(4) {
+                px += rng.range(1..=10);
+                py += rng.below(10);
+            }
+            format!("Button A: X+{ax}, Y+{ay}\nButton B: X+{bx}, Y+{by}\nPrize: X={px}, Y={py}")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
 }
@@ -1,9 +1,13 @@
-#![allow(dead_code, unused_variables)]
-use std::collections::{HashMap, HashSet};
+//! Cell by cell implementations: part 1 shifts crates along a line on a grid, part 2 has
+//! separate crate halves in a map and pushes them recursively. Kept around to cross-check
+//! [`super::warehouse`].
 
-use aoc_runner_derive::{aoc, aoc_generator};
+use std::collections::{HashMap, HashSet};
 
-use crate::{Point, TwoDee};
+use crate::{
+    trace::{Event, Level, Trace},
+    Point, TwoDee,
+};
 
 impl std::fmt::Debug for TwoDee<S> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -32,15 +36,14 @@ impl std::fmt::Debug for TwoDee<S> {
     }
 }
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
-enum S {
+pub(super) enum S {
     Wall,
     Crate,
     Robot,
     Space,
 }
 
-#[aoc_generator(day15)]
-fn parse(input: &str) -> (TwoDee<S>, Point, Vec<Point>) {
+pub(super) fn parse(input: &str) -> (TwoDee<S>, Point, Vec<Point>) {
     let (maze, code) = input.split_once("\n\n").unwrap();
     let maze = maze
         .lines()
@@ -82,13 +85,16 @@ fn parse(input: &str) -> (TwoDee<S>, Point, Vec<Point>) {
     (maze, robot, prog)
 }
 
-#[aoc(day15, part1)]
-fn part1(input: &(TwoDee<S>, Point, Vec<Point>)) -> usize {
+pub(super) fn part1(input: &(TwoDee<S>, Point, Vec<Point>), trace: &Trace) -> usize {
     let mut maze = input.0.clone();
     let mut robot = input.1;
 
-    'outer: for dir in input.2.iter().copied() {
+    'outer: for (ix, dir) in input.2.iter().copied().enumerate() {
         assert_eq!(maze[robot], S::Robot);
+        trace.emit(Level::Debug, || Event::Frame {
+            step: ix,
+            grid: &maze,
+        });
 
         let mut dist = 0;
 
@@ -96,6 +102,12 @@ fn part1(input: &(TwoDee<S>, Point, Vec<Point>)) -> usize {
             if maze[robot + dir * dist] == S::Wall {
                 continue 'outer;
             } else if maze[robot + dir * dist] == S::Space {
+                if dist > 1 {
+                    trace.emit(Level::Trace, || Event::Move {
+                        from: (robot + dir).into(),
+                        to: (robot + dir * dist).into(),
+                    });
+                }
                 maze[robot] = S::Space;
                 maze[robot + dir * dist] = maze[robot + dir];
                 maze[robot + dir] = S::Robot;
@@ -107,6 +119,11 @@ fn part1(input: &(TwoDee<S>, Point, Vec<Point>)) -> usize {
         }
     }
 
+    trace.emit(Level::Debug, || Event::Frame {
+        step: input.2.len(),
+        grid: &maze,
+    });
+
     let mut gps = 0;
 
     for x in 0..maze.width as i32 {
@@ -128,8 +145,7 @@ enum SS {
     Space,
 }
 
-#[aoc(day15, part2)]
-fn part2(input: &(TwoDee<S>, Point, Vec<Point>)) -> usize {
+pub(super) fn part2(input: &(TwoDee<S>, Point, Vec<Point>), trace: &Trace) -> usize {
     let maze = input.0.clone();
     let mut robot = input.1;
     robot.x *= 2;
@@ -158,6 +174,11 @@ fn part2(input: &(TwoDee<S>, Point, Vec<Point>)) -> usize {
     let mut maze = m;
 
     'outer: for (ix, dir) in input.2.iter().copied().enumerate() {
+        let view = Wide(&maze, robot);
+        trace.emit(Level::Debug, || Event::Frame {
+            step: ix,
+            grid: &view,
+        });
         if dir == Point::L || dir == Point::R {
             let mut dist = 1;
             loop {
@@ -165,7 +186,7 @@ fn part2(input: &(TwoDee<S>, Point, Vec<Point>)) -> usize {
                     continue 'outer;
                 } else if maze[&(robot + dir * dist)] == SS::Space {
                     for i in (1..=dist).rev() {
-                        swap(&mut maze, robot + dir * i, robot + dir * (i - 1), robot);
+                        swap(&mut maze, robot + dir * i, robot + dir * (i - 1), trace);
                     }
                     robot = robot + dir;
                     continue 'outer;
@@ -184,13 +205,18 @@ fn part2(input: &(TwoDee<S>, Point, Vec<Point>)) -> usize {
             continue 'outer;
         } else if go(&maze, dir, h, &mut out) {
             for p in out.iter().rev().copied() {
-                println!("swapping {:?} <-> {:?}", p, p + dir);
-                swap(&mut maze, p + dir, p, robot);
+                swap(&mut maze, p + dir, p, trace);
             }
             robot = robot + dir;
         }
     }
 
+    let view = Wide(&maze, robot);
+    trace.emit(Level::Debug, || Event::Frame {
+        step: input.2.len(),
+        grid: &view,
+    });
+
     // and now something complitely different..
     // At what point do I say screw it, I'm done?
 
@@ -202,30 +228,42 @@ fn part2(input: &(TwoDee<S>, Point, Vec<Point>)) -> usize {
     }
     out as usize
 }
-fn dump(maze: &HashMap<Point, SS>, robot: Point) {
-    let w = 10;
-    for y in 0..w {
-        for x in 0..w * 2 {
-            let p = Point { x, y };
+/// Wide warehouse along with the robot, for frames
+struct Wide<'a>(&'a HashMap<Point, SS>, Point);
 
-            if p == robot {
-                print!("@");
-            } else {
-                match maze[&p] {
-                    SS::Wall => print!("#"),
-                    SS::CrateL => print!("["),
-                    SS::CrateR => print!("]"),
-                    SS::Space => print!("."),
+impl std::fmt::Debug for Wide<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let Wide(maze, robot) = self;
+        let w = maze.keys().map(|p| p.x).max().unwrap_or(0);
+        let h = maze.keys().map(|p| p.y).max().unwrap_or(0);
+        writeln!(f)?;
+        for y in 0..=h {
+            for x in 0..=w {
+                let p = Point { x, y };
+
+                if p == *robot {
+                    write!(f, "@")?;
+                } else {
+                    match maze[&p] {
+                        SS::Wall => write!(f, "#"),
+                        SS::CrateL => write!(f, "["),
+                        SS::CrateR => write!(f, "]"),
+                        SS::Space => write!(f, "."),
+                    }?
                 }
             }
+            writeln!(f)?;
         }
-        println!();
+        Ok(())
     }
-    println!();
 }
 
-fn swap(maze: &mut HashMap<Point, SS>, to: Point, from: Point, robot: Point) {
+fn swap(maze: &mut HashMap<Point, SS>, to: Point, from: Point, trace: &Trace) {
     assert_eq!(maze[&to], SS::Space);
+    trace.emit(Level::Trace, || Event::Move {
+        from: from.into(),
+        to: to.into(),
+    });
 
     let tmp = maze.remove(&from).unwrap();
     maze.insert(from, maze[&to]);
@@ -258,62 +296,3 @@ fn go(
         go(maze, dir, pp2, out)
     }
 }
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn part1_example() {
-        let input = "\
-##########
-#..O..O.O#
-#......O.#
-#.OO..O.O#
-#..O@..O.#
-#O#..O...#
-#O..O..O.#
-#.OO.O.OO#
-#....O...#
-##########
-
-<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
-vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
-><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
-<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
-^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
-^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
->^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
-<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
-^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
-v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
-        assert_eq!(part1(&parse(input)), 10092);
-    }
-
-    #[test]
-    fn part2_example() {
-        let input = "\
-##########
-#..O..O.O#
-#......O.#
-#.OO..O.O#
-#..O@..O.#
-#O#..O...#
-#O..O..O.#
-#.OO.O.OO#
-#....O...#
-##########
-
-<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
-vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
-><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
-<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
-^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
-^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
->^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
-<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
-^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
-v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
-        assert_eq!(part2(&parse(input)), 9021);
-    }
-}
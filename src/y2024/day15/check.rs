@@ -0,0 +1,292 @@
+//! Invariants every warehouse run must keep, checked on the frames solvers emit
+//!
+//! Frames are the one thing both the engine and the cell by cell solvers produce, so the
+//! checks work on rendered text: crates are never destroyed, walls never move, there is exactly
+//! one robot and every `[` is closed by a `]` on the same row with only `=` in between. Runs
+//! are only checked when asked to with `check_moves`, [`FUZZ`] checks every move of small
+//! random warehouses and shrinks any run that breaks an invariant.
+
+use std::{cell::RefCell, rc::Rc};
+
+use crate::{
+    fuzz::{Fuzz, Rng},
+    runner::Ctx,
+    trace::{Event, Level, Sink, Trace},
+};
+
+pub(crate) const FUZZ: Fuzz = Fuzz {
+    day: 15,
+    generate,
+    shrink,
+    // far longer than any generated run, so every move is checked
+    params: &[("check_moves", "1000000")],
+};
+
+/// What the first frame of a run looked like
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Invariants {
+    width: usize,
+    height: usize,
+    /// Row and column of every wall
+    walls: Vec<(usize, usize)>,
+    crates: usize,
+}
+
+impl Invariants {
+    pub fn new(frame: &str) -> Result<Self, String> {
+        let rows = frame.trim_start_matches('\n').lines().collect::<Vec<_>>();
+        Ok(Self {
+            width: rows.first().map_or(0, |r| r.len()),
+            height: rows.len(),
+            walls: walls(&rows),
+            crates: crates(&rows)?,
+        })
+    }
+
+    /// Frame keeps the same walls and crates as the first one and has exactly one robot
+    pub fn check(&self, frame: &str) -> Result<(), String> {
+        let rows = frame.trim_start_matches('\n').lines().collect::<Vec<_>>();
+        if rows.len() != self.height || rows.iter().any(|r| r.len() != self.width) {
+            return Err(format!(
+                "warehouse is no longer {}×{}",
+                self.width, self.height
+            ));
+        }
+        // both are in reading order, first difference is the wall that moved
+        let walls = walls(&rows);
+        if walls != self.walls {
+            if let Some(w) = self.walls.iter().find(|w| walls.binary_search(w).is_err()) {
+                return Err(format!("wall at row {} column {} is gone", w.0, w.1));
+            }
+            if let Some(w) = walls.iter().find(|w| self.walls.binary_search(w).is_err()) {
+                return Err(format!("new wall at row {} column {}", w.0, w.1));
+            }
+        }
+        let crates = crates(&rows)?;
+        if crates != self.crates {
+            return Err(format!("{} crates, started with {}", crates, self.crates));
+        }
+        match frame.matches('@').count() {
+            1 => Ok(()),
+            n => Err(format!("{n} robots")),
+        }
+    }
+}
+
+fn walls(rows: &[&str]) -> Vec<(usize, usize)> {
+    let cells = rows.iter().enumerate().flat_map(|(y, row)| {
+        let walls = row.bytes().enumerate().filter(|c| c.1 == b'#');
+        walls.map(move |(x, _)| (y, x))
+    });
+    cells.collect()
+}
+
+/// Number of crate pieces, `O` or `[`, every `[` must be closed on the same row
+fn crates(rows: &[&str]) -> Result<usize, String> {
+    let mut count = 0;
+    for (y, row) in rows.iter().enumerate() {
+        let mut open = None;
+        for (x, c) in row.bytes().enumerate() {
+            match (c, open) {
+                (b'=', Some(_)) => {}
+                (b']', Some(_)) => open = None,
+                (_, Some(x)) => {
+                    return Err(format!("crate at row {y} column {x} has no right half"))
+                }
+                (b'[', None) => {
+                    open = Some(x);
+                    count += 1;
+                }
+                (b'O', None) => count += 1,
+                (b'=' | b']', None) => {
+                    return Err(format!("crate at row {y} column {x} has no left half"))
+                }
+                _ => {}
+            }
+        }
+        if let Some(x) = open {
+            return Err(format!("crate at row {y} column {x} has no right half"));
+        }
+    }
+    Ok(count)
+}
+
+/// Checks every frame against the first one and passes all events on
+struct Checker {
+    first: Option<Invariants>,
+    inner: Trace,
+    /// First broken invariant along with the frame step
+    broken: Rc<RefCell<Option<(usize, String)>>>,
+}
+
+impl Sink for Checker {
+    fn event(&mut self, level: Level, event: &Event) {
+        self.inner.forward(level, event);
+        let Event::Frame { step, grid } = event else {
+            return;
+        };
+        let mut broken = self.broken.borrow_mut();
+        if broken.is_some() {
+            return;
+        }
+        let frame = format!("{grid:?}");
+        let res = match &self.first {
+            Some(first) => first.check(&frame),
+            None => Invariants::new(&frame).map(|first| self.first = Some(first)),
+        };
+        if let Err(err) = res {
+            *broken = Some((*step, format!("{err}:{frame}")));
+        }
+    }
+}
+
+/// Run a solver with every frame checked, events still reach `trace`
+pub(super) fn checked<T>(trace: &Trace, run: impl FnOnce(&Trace) -> T) -> Result<T, String> {
+    let broken = Rc::new(RefCell::new(None));
+    let checker = Checker {
+        first: None,
+        inner: trace.clone(),
+        broken: broken.clone(),
+    };
+    let level = if trace.enabled(Level::Trace) {
+        Level::Trace
+    } else {
+        Level::Debug
+    };
+    let res = run(&Trace::new(level, checker));
+    match broken.take() {
+        None => Ok(res),
+        Some((0, err)) => Err(format!("at the start {err}")),
+        Some((step, err)) => Err(format!("after move {} {err}", step - 1)),
+    }
+}
+
+/// Run the solver with [`checked`] when there are at most `check_moves` moves, panic when an
+/// invariant breaks. Off unless `check_moves` is set.
+pub(super) fn debug_checked<T>(ctx: &Ctx, moves: usize, run: impl FnOnce(&Trace) -> T) -> T {
+    let params: super::Params = ctx.get();
+    if moves <= params.check_moves {
+        checked(&ctx.trace, run).unwrap_or_else(|err| panic!("{err}"))
+    } else {
+        run(&ctx.trace)
+    }
+}
+
+/// Square warehouse walled all around with a few walls and crates inside, then some moves
+fn generate(rng: &mut Rng) -> String {
+    let size = rng.range(4..=10);
+    let mut map = vec![vec![b'#'; size]; size];
+    for row in &mut map[1..size - 1] {
+        for c in &mut row[1..size - 1] {
+            *c = match rng.below(8) {
+                0 => b'#',
+                1..=3 => b'O',
+                _ => b'.',
+            };
+        }
+    }
+    map[rng.range(1..=size - 2)][rng.range(1..=size - 2)] = b'@';
+    let moves = (0..rng.range(1..=40))
+        .map(|_| rng.pick(&['<', '>', '^', 'v']))
+        .collect::<String>();
+    let map = map.into_iter().map(|r| String::from_utf8(r).unwrap());
+    format!("{}\n\n{moves}\n", map.collect::<Vec<_>>().join("\n"))
+}
+
+/// Drop a move, clear a cell or drop an inside row along with the matching column, the
+/// warehouse stays square and walled in
+fn shrink(input: &str) -> Vec<String> {
+    let Some((map, moves)) = input.split_once("\n\n") else {
+        return Vec::new();
+    };
+    let rows = map
+        .lines()
+        .map(|r| r.as_bytes().to_vec())
+        .collect::<Vec<_>>();
+    let moves = moves.trim().as_bytes();
+    let join = |rows: &[Vec<u8>], moves: &[u8]| {
+        let map = rows.iter().map(|r| String::from_utf8_lossy(r).into_owned());
+        let moves = String::from_utf8_lossy(moves);
+        format!("{}\n\n{moves}\n", map.collect::<Vec<_>>().join("\n"))
+    };
+
+    let mut res = Vec::new();
+    let size = rows.len();
+    for ix in 1..size.saturating_sub(1) {
+        if rows[ix].contains(&b'@') || rows.iter().any(|r| r[ix] == b'@') {
+            continue;
+        }
+        let smaller = rows
+            .iter()
+            .enumerate()
+            .filter(|(y, _)| *y != ix)
+            .map(|(_, r)| [&r[..ix], &r[ix + 1..]].concat())
+            .collect::<Vec<_>>();
+        res.push(join(&smaller, moves));
+    }
+    for ix in 0..moves.len() {
+        res.push(join(&rows, &[&moves[..ix], &moves[ix + 1..]].concat()));
+    }
+    for y in 1..size.saturating_sub(1) {
+        for x in 1..size - 1 {
+            if matches!(rows[y][x], b'#' | b'O') {
+                let mut cleared = rows.clone();
+                cleared[y][x] = b'.';
+                res.push(join(&cleared, moves));
+            }
+        }
+    }
+    res
+}
+
+#[test]
+fn invariants_work() {
+    let start = Invariants::new("\n#####\n#@[]#\n#.O.#\n#####\n").unwrap();
+    assert_eq!(start.crates, 2);
+    assert_eq!(
+        start.check("\n#####\n#.@[]\n#..O#\n#####\n"),
+        Err("wall at row 1 column 4 is gone".to_owned())
+    );
+    assert!(start.check("\n#####\n#.@[]#\n#...#\n#####\n").is_err());
+    assert!(start.check("\n#####\n#.@[#\n#.O.#\n#####\n").is_err());
+    assert!(start.check("\n#####\n#@.]#\n#.O.#\n#####\n").is_err());
+    assert_eq!(
+        start.check("\n#####\n#.[]#\n#.O.#\n#####\n"),
+        Err("0 robots".to_owned())
+    );
+    assert_eq!(start.check("\n#####\n#.[]#\n#@O.#\n#####\n"), Ok(()));
+
+    let input = "#####\n#O.@#\n#.O.#\n#...#\n#####\n\n<<v<^\n";
+    let (warehouse, moves) = super::parse(input);
+    let frames = checked(&Trace::default(), |t| {
+        warehouse.scaled(3, 2).run(&moves, t);
+    });
+    assert_eq!(frames, Ok(()));
+
+    // a solver that loses a crate on the way
+    let frames = ["#####\n#O.@#\n#.O.#\n#####", "#####\n#O@.#\n#...#\n#####"];
+    let lossy = checked(&Trace::default(), |t| {
+        for (step, frame) in frames.iter().enumerate() {
+            let w = super::Warehouse::parse(frame).unwrap();
+            t.emit(Level::Debug, || Event::Frame { step, grid: &w });
+        }
+    });
+    assert!(lossy
+        .unwrap_err()
+        .starts_with("after move 0 1 crates, started with 2:"));
+}
+
+#[test]
+fn shrink_stays_valid() {
+    // moves, walls and crates left, every shrink step takes at least one away
+    let weight = |s: &str| s.bytes().filter(|c| !b".@\n".contains(c)).count();
+    let mut rng = Rng::new(15);
+    for _ in 0..20 {
+        let input = generate(&mut rng);
+        for smaller in shrink(&input) {
+            let (map, _) = super::parse(&smaller);
+            assert_eq!(map.width, map.height, "{smaller}");
+            assert!(weight(&smaller) < weight(&input));
+        }
+    }
+}
@@ -0,0 +1,336 @@
+//! Recorded moves, for going back and forth through a run
+//!
+//! Every move is stored as a [`Delta`]: direction, crates that moved with the robot and
+//! whether it was blocked. Applying a delta again or backwards is a plain shift, so undo, redo
+//! and seeking don't need to look at the rules at all.
+
+use std::{cell::RefCell, fmt, rc::Rc};
+
+use super::warehouse::Warehouse;
+use crate::{
+    runner::{Overrides, Year},
+    trace::{Event, Level, Sink, Trace},
+    Point,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Delta {
+    pub dir: Point,
+    /// Crates that moved along with the robot
+    pub crates: Vec<usize>,
+    pub blocked: bool,
+}
+
+impl Delta {
+    /// Cells that look different after the move, `after` is the warehouse with the move
+    /// applied
+    pub fn cells(&self, after: &Warehouse) -> Vec<Point> {
+        if self.blocked {
+            return Vec::new();
+        }
+        let mut cells = vec![after.robot - self.dir, after.robot];
+        for &id in &self.crates {
+            let now = after.crates[id].cells().collect::<Vec<_>>();
+            let before = now.iter().map(|&p| p - self.dir).collect::<Vec<_>>();
+            cells.extend(now.iter().filter(|p| !before.contains(p)));
+            cells.extend(before.iter().filter(|p| !now.contains(p)));
+        }
+        cells.sort();
+        cells.dedup();
+        cells
+    }
+}
+
+impl fmt::Display for Delta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.crates.len() {
+            _ if self.blocked => write!(f, "{} blocked", self.dir),
+            0 => write!(f, "{}", self.dir),
+            n => write!(f, "{} pushed {n} crates", self.dir),
+        }
+    }
+}
+
+/// Warehouse along with moves that led to it and moves that were undone
+#[derive(Clone)]
+pub(super) struct History {
+    pub warehouse: Warehouse,
+    done: Vec<Delta>,
+    /// Most recently undone last
+    undone: Vec<Delta>,
+}
+
+impl History {
+    pub fn new(warehouse: Warehouse) -> Self {
+        Self {
+            warehouse,
+            done: Vec::new(),
+            undone: Vec::new(),
+        }
+    }
+
+    /// Record all the moves and go back to the start
+    pub fn replay(warehouse: Warehouse, moves: &[Point]) -> Self {
+        let mut history = Self::new(warehouse);
+        for &dir in moves {
+            history.push(dir, &Trace::default());
+        }
+        history.seek(0);
+        history
+    }
+
+    /// Number of moves made
+    pub fn position(&self) -> usize {
+        self.done.len()
+    }
+
+    /// Number of moves made and undone
+    pub fn len(&self) -> usize {
+        self.done.len() + self.undone.len()
+    }
+
+    /// Every recorded move, including undone ones
+    pub fn deltas(&self) -> impl Iterator<Item = &Delta> {
+        self.done.iter().chain(self.undone.iter().rev())
+    }
+
+    pub fn last(&self) -> Option<&Delta> {
+        self.done.last()
+    }
+
+    /// Make a new move, anything undone is forgotten
+    pub fn push(&mut self, dir: Point, trace: &Trace) -> &Delta {
+        self.undone.clear();
+        let delta = match self.warehouse.pushed(dir) {
+            Some(crates) => {
+                self.warehouse.shift(&crates, dir, trace);
+                Delta {
+                    dir,
+                    crates,
+                    blocked: false,
+                }
+            }
+            None => Delta {
+                dir,
+                crates: Vec::new(),
+                blocked: true,
+            },
+        };
+        self.done.push(delta);
+        &self.done[self.done.len() - 1]
+    }
+
+    pub fn undo(&mut self) -> bool {
+        let Some(delta) = self.done.pop() else {
+            return false;
+        };
+        if !delta.blocked {
+            let back = Point { x: 0, y: 0 } - delta.dir;
+            self.warehouse.shift(&delta.crates, back, &Trace::default());
+        }
+        self.undone.push(delta);
+        true
+    }
+
+    pub fn redo(&mut self) -> bool {
+        let Some(delta) = self.undone.pop() else {
+            return false;
+        };
+        if !delta.blocked {
+            self.warehouse
+                .shift(&delta.crates, delta.dir, &Trace::default());
+        }
+        self.done.push(delta);
+        true
+    }
+
+    /// Moves made so far in puzzle format
+    pub fn moves(&self) -> String {
+        super::show_moves(&self.done.iter().map(|d| d.dir).collect::<Vec<_>>())
+    }
+
+    /// Undo or redo until `n` moves are made, as far as recorded moves go
+    pub fn seek(&mut self, n: usize) -> usize {
+        while self.position() > n && self.undo() {}
+        while self.position() < n && self.redo() {}
+        self.position()
+    }
+}
+
+/// Frames as text, state before every move and after the last one
+struct Frames(Rc<RefCell<Vec<String>>>);
+
+impl Sink for Frames {
+    fn event(&mut self, _level: Level, event: &Event) {
+        if let Event::Frame { grid, .. } = event {
+            self.0.borrow_mut().push(format!("{grid:?}"));
+        }
+    }
+}
+
+/// Run a solver with a trace that records its frames
+pub(super) fn frames(run: impl FnOnce(&Trace)) -> Vec<String> {
+    let frames = Rc::new(RefCell::new(Vec::new()));
+    run(&Trace::new(Level::Debug, Frames(frames.clone())));
+    frames.take()
+}
+
+/// Number of moves after which two runs stop agreeing
+pub(super) fn diverge(a: &[String], b: &[String]) -> Option<usize> {
+    match a.iter().zip(b).position(|(a, b)| a != b) {
+        Some(ix) => Some(ix),
+        None if a.len() != b.len() => Some(a.len().min(b.len())),
+        None => None,
+    }
+}
+
+/// Run the engine and the cell by cell solvers on the same input and show the first move they
+/// disagree on, only at the 2×1 scale the cell by cell solvers know
+pub(super) fn compare(year: &Year, args: &[String]) -> Result<(), String> {
+    let (cli, args) = Overrides::from_args(args)?;
+    let path = match args.as_slice() {
+        [] => None,
+        [path] => Some(path.as_str()),
+        _ => return Err("Expected at most one file".to_owned()),
+    };
+    let (input, params) = super::tool_input(year, path, &cli)?;
+    params.cells_scale()?;
+    let engine = super::parse(&input);
+    let cells = super::cells::parse(&input);
+    let runs = [
+        (
+            1,
+            frames(|t| {
+                super::part1(&engine, t);
+            }),
+            frames(|t| {
+                super::cells::part1(&cells, t);
+            }),
+        ),
+        (
+            2,
+            frames(|t| {
+                super::part2(&engine, &params, t);
+            }),
+            frames(|t| {
+                super::cells::part2(&cells, t);
+            }),
+        ),
+    ];
+    for (part, a, b) in runs {
+        let Some(n) = diverge(&a, &b) else {
+            println!("part {part}: same after each of {} moves", engine.1.len());
+            continue;
+        };
+        let dir = n
+            .checked_sub(1)
+            .map_or("start".to_owned(), |m| format!("move {m} {}", engine.1[m]));
+        println!("part {part}: different after {dir}");
+        let show = |f: &[String]| f.get(n).cloned().unwrap_or_else(|| "\nmissing".to_owned());
+        println!("engine:{}cells:{}", show(&a), show(&b));
+    }
+    Ok(())
+}
+
+/// Part 2 warehouse after a given number of moves along with the move that led there
+pub(super) fn seek(year: &Year, args: &[String]) -> Result<(), String> {
+    let (cli, args) = Overrides::from_args(args)?;
+    let (path, n) = match args.as_slice() {
+        [n] => (None, n),
+        [path, n] => (Some(path.as_str()), n),
+        _ => return Err("Expected [FILE] N".to_owned()),
+    };
+    let n = n.parse().map_err(|_| format!("Bad move number: {n}"))?;
+    let (input, params) = super::tool_input(year, path, &cli)?;
+    let (warehouse, moves) = super::parse(&input);
+    let mut history = History::replay(warehouse.scaled(params.wide, params.tall), &moves);
+    let at = history.seek(n);
+    print!("{}", history.warehouse);
+    match history.last() {
+        Some(delta) => {
+            let cells = delta.cells(&history.warehouse);
+            let cells = cells.iter().map(|p| format!("{},{}", p.x, p.y));
+            println!(
+                "after {at} of {} moves, last one {delta}, changed {}",
+                history.len(),
+                cells.collect::<Vec<_>>().join(" ")
+            );
+        }
+        None => println!("at the start, {} moves", history.len()),
+    }
+    let blocked = history.deltas().take(at).filter(|d| d.blocked).count();
+    println!(
+        "{blocked} moves blocked so far, GPS {}",
+        history.warehouse.gps()
+    );
+    Ok(())
+}
+
+#[test]
+fn history_works() {
+    let year = &crate::y2024::YEAR;
+    let (warehouse, moves) = super::parse(&year.example("day15").input);
+    let warehouse = warehouse.scaled(2, 1);
+    let mut history = History::replay(warehouse.clone(), &moves);
+    assert_eq!(history.warehouse, warehouse);
+    assert_eq!(history.len(), moves.len());
+
+    let after = |n: usize| {
+        let mut w = warehouse.clone();
+        w.run(&moves[..n], &Trace::default());
+        w
+    };
+    for n in [moves.len(), 1, 300, 17, 0, 700] {
+        assert_eq!(history.seek(n), n);
+        assert_eq!(history.warehouse, after(n), "after {n} moves");
+    }
+    assert_eq!(history.warehouse.gps(), 9021);
+
+    history.seek(300);
+    let delta = history.last().unwrap().clone();
+    let before = after(299);
+    let changed = (0..before.height)
+        .flat_map(|y| (0..before.width).map(move |x| Point::new(x, y)))
+        .filter(|&p| {
+            let show = |w: &Warehouse| {
+                w.to_string().lines().nth(p.y as usize).unwrap().as_bytes()[p.x as usize]
+            };
+            show(&before) != show(&history.warehouse)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(delta.cells(&history.warehouse), changed);
+
+    // a new move after undo forgets the rest
+    history.push(Point::L, &Trace::default());
+    assert_eq!(history.len(), 301);
+    assert!(!history.redo());
+    assert!(history.undo());
+    assert_eq!(history.warehouse, after(300));
+}
+
+#[test]
+fn diverge_works() {
+    let year = &crate::y2024::YEAR;
+    let input = year.example("day15").input;
+    let engine = super::parse(&input);
+    let cells = super::cells::parse(&input);
+    let params = super::Params::default();
+
+    let a = frames(|t| {
+        super::part2(&engine, &params, t);
+    });
+    let b = frames(|t| {
+        super::cells::part2(&cells, t);
+    });
+    assert_eq!(a.len(), engine.1.len() + 1);
+    assert_eq!(diverge(&a, &b), None);
+
+    // skip a move that isn't blocked, the next state is different
+    let history = History::replay(engine.0.scaled(2, 1), &engine.1);
+    let skip = 40 + history.deltas().skip(40).position(|d| !d.blocked).unwrap();
+    let mut moves = engine.1.clone();
+    moves.remove(skip);
+    let mut other = engine.0.scaled(2, 1);
+    let c = frames(|t| other.run(&moves, t));
+    assert_eq!(diverge(&a, &c), Some(skip + 1));
+}
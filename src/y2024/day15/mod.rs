@@ -0,0 +1,141 @@
+//! Robot pushing crates around a warehouse
+
+mod cells;
+mod check;
+mod history;
+mod plan;
+mod play;
+mod warehouse;
+
+use crate::{
+    runner::{params, Overrides, Solution, Tool, Year},
+    trace::Trace,
+    Point,
+};
+use check::debug_checked;
+use warehouse::Warehouse;
+
+pub(crate) use check::FUZZ;
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(15, 1, |i, p| {
+        let input = parse(i);
+        debug_checked(p, input.1.len(), |t| part1(&input, t)).to_string()
+    }),
+    Solution::new(15, 1, |i, p| {
+        let input = cells::parse(i);
+        debug_checked(p, input.2.len(), |t| cells::part1(&input, t)).to_string()
+    })
+    .named("cells"),
+    Solution::new(15, 2, |i, p| {
+        let input = parse(i);
+        debug_checked(p, input.1.len(), |t| part2(&input, &p.get(), t)).to_string()
+    }),
+    Solution::new(15, 2, |i, p| {
+        let params: Params = p.get();
+        params.cells_scale().unwrap_or_else(|err| panic!("{err}"));
+        let input = cells::parse(i);
+        debug_checked(p, input.2.len(), |t| cells::part2(&input, t)).to_string()
+    })
+    .named("cells"),
+];
+
+pub(crate) const TOOLS: &[Tool] = &[
+    Tool {
+        name: "compare",
+        usage: "[FILE] [--param KEY=VALUE]...  First move where the day 15 engine and cell by cell solvers disagree",
+        run: history::compare,
+    },
+    Tool {
+        name: "seek",
+        usage: "[FILE] N [--param KEY=VALUE]...  Day 15 part 2 warehouse after N moves and what the last move changed",
+        run: history::seek,
+    },
+    Tool {
+        name: "play",
+        usage: "[FILE] [--wide] [--save PATH] [--param KEY=VALUE]...  Push day 15 crates around by hand",
+        run: play::play,
+    },
+    Tool {
+        name: "plan",
+        usage: "FILE X,Y... [--wide] [--limit N] [--param KEY=VALUE]...  Shortest day 15 moves putting crates on targets, --wide plans for part 2 and still prints the narrow map",
+        run: plan::tool,
+    },
+];
+
+params! {
+    /// Part 2 warehouse is this many times wider
+    wide: usize = 2,
+    /// and this many times taller
+    tall: usize = 1,
+    /// Check invariants after every move of runs up to this long, 0 for no checks
+    check_moves: usize = 0,
+}
+
+impl Params {
+    /// Cell by cell solvers only know the 2×1 scale from the puzzle
+    fn cells_scale(&self) -> Result<(), String> {
+        match (self.wide, self.tall) {
+            (2, 1) => Ok(()),
+            (w, t) => Err(format!(
+                "cell by cell part 2 only scales by 2×1, not {w}×{t}"
+            )),
+        }
+    }
+}
+
+fn parse(input: &str) -> (Warehouse, Vec<Point>) {
+    let (map, moves) = input.split_once("\n\n").unwrap();
+    let warehouse = Warehouse::parse(map).unwrap_or_else(|err| panic!("{err}"));
+    (warehouse, self::moves(moves))
+}
+
+/// Moves in puzzle format, anything other than `<>^v` is ignored
+fn moves(moves: &str) -> Vec<Point> {
+    moves
+        .chars()
+        .filter_map(|c| {
+            Some(match c {
+                '<' => Point::L,
+                '>' => Point::R,
+                '^' => Point::U,
+                'v' => Point::D,
+                _ => return None,
+            })
+        })
+        .collect()
+}
+
+/// Input for a tool along with parameters from its header overridden by `--param` from its
+/// arguments
+fn tool_input(
+    year: &Year,
+    path: Option<&str>,
+    cli: &Overrides,
+) -> Result<(String, Params), String> {
+    year.check_params(&[15], cli)?;
+    let (input, mut params) = year.tool_input(15, path)?;
+    params.extend(cli);
+    Ok((input, params.try_get()?))
+}
+
+/// Moves in puzzle format, 70 to a line
+fn show_moves(moves: &[Point]) -> String {
+    let moves = moves.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+    let lines = moves.chunks(70).map(|l| l.concat()).collect::<Vec<_>>();
+    lines.join("\n")
+}
+
+fn part1(input: &(Warehouse, Vec<Point>), trace: &Trace) -> usize {
+    let (warehouse, moves) = input;
+    let mut warehouse = warehouse.clone();
+    warehouse.run(moves, trace);
+    warehouse.gps()
+}
+
+fn part2(input: &(Warehouse, Vec<Point>), params: &Params, trace: &Trace) -> usize {
+    let (warehouse, moves) = input;
+    let mut warehouse = warehouse.scaled(params.wide, params.tall);
+    warehouse.run(moves, trace);
+    warehouse.gps()
+}
@@ -0,0 +1,199 @@
+//! Planning moves that put crates on target cells
+//!
+//! Breadth first search over warehouse states, the robot along with every crate, so the first
+//! plan found is the shortest. A crate counts as on a target when its top left corner is there.
+//! States where fewer crates than targets are on live cells are dropped right away: live cells
+//! are the ones a crate can be pulled to from some target, from anywhere else it can never be
+//! pushed onto one.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use super::warehouse::{Tile, Warehouse};
+use crate::{
+    runner::{Overrides, Year},
+    trace::Trace,
+    Point,
+};
+
+/// Robot and sorted crate positions with their sizes, crates of the same size are
+/// interchangeable
+type Key = (Point, Vec<(Point, i32, i32)>);
+
+fn key(w: &Warehouse) -> Key {
+    let mut crates = w
+        .crates
+        .iter()
+        .map(|c| (c.pos, c.width, c.height))
+        .collect::<Vec<_>>();
+    crates.sort_unstable_by_key(|c| (c.0.y, c.0.x, c.1, c.2));
+    (w.robot, crates)
+}
+
+/// Cells a crate of this size can be pushed from onto some target, only walls are in the way
+pub(super) fn live_cells(
+    w: &Warehouse,
+    (width, height): (i32, i32),
+    targets: &[Point],
+) -> HashSet<Point> {
+    let cells = |pos: Point| {
+        (0..height).flat_map(move |dy| (0..width).map(move |dx| pos + Point { x: dx, y: dy }))
+    };
+    let fits = |pos| cells(pos).all(|p| w.tile(p) != Tile::Wall);
+    let mut live = targets
+        .iter()
+        .copied()
+        .filter(|&t| fits(t))
+        .collect::<HashSet<_>>();
+    let mut todo = live.iter().copied().collect::<Vec<_>>();
+    while let Some(to) = todo.pop() {
+        for dir in Point::DIRS {
+            // pushed from `from` to `to` by a robot right behind it
+            let from = to - dir;
+            let behind = cells(from)
+                .map(|p| p - dir)
+                .any(|p| !cells(from).any(|c| c == p) && w.tile(p) != Tile::Wall);
+            if behind && fits(from) && live.insert(from) {
+                todo.push(from);
+            }
+        }
+    }
+    live
+}
+
+/// Shortest moves putting a crate on every target, `None` if there are none. Gives up after
+/// looking at `limit` states.
+pub(super) fn plan(
+    start: &Warehouse,
+    targets: &[Point],
+    limit: usize,
+) -> Result<Option<Vec<Point>>, String> {
+    let mut live = HashMap::new();
+    for c in &start.crates {
+        let size = (c.width, c.height);
+        live.entry(size)
+            .or_insert_with(|| live_cells(start, size, targets));
+    }
+    let hopeful = |w: &Warehouse| {
+        let alive = w
+            .crates
+            .iter()
+            .filter(|c| live[&(c.width, c.height)].contains(&c.pos));
+        alive.count() >= targets.len()
+    };
+    let done = |w: &Warehouse| targets.iter().all(|t| w.crates.iter().any(|c| c.pos == *t));
+
+    // move that led to each state and the state it was made from
+    let mut steps: Vec<(Point, Option<usize>)> = Vec::new();
+    let mut seen = HashSet::from([key(start)]);
+    let mut queue = VecDeque::from([(start.clone(), None)]);
+    while let Some((w, at)) = queue.pop_front() {
+        if done(&w) {
+            let mut moves = Vec::new();
+            let mut at = at;
+            while let Some(ix) = at {
+                let (dir, from) = steps[ix];
+                moves.push(dir);
+                at = from;
+            }
+            moves.reverse();
+            return Ok(Some(moves));
+        }
+        if !hopeful(&w) {
+            continue;
+        }
+        for dir in Point::DIRS {
+            let mut next = w.clone();
+            if !next.push(dir, &Trace::default()) || !seen.insert(key(&next)) {
+                continue;
+            }
+            if seen.len() > limit {
+                return Err(format!("gave up after {limit} states"));
+            }
+            steps.push((dir, at));
+            queue.push_back((next, Some(steps.len() - 1)));
+        }
+    }
+    Ok(None)
+}
+
+/// Plan moves for a warehouse from a file and print it in puzzle format. With `--wide` the
+/// targets and moves are for the part 2 warehouse, the map stays the narrow one so the output
+/// reads back as a puzzle input.
+pub(super) fn tool(year: &Year, args: &[String]) -> Result<(), String> {
+    let (cli, args) = Overrides::from_args(args)?;
+    let mut path = None;
+    let mut wide = false;
+    let mut limit = 1_000_000;
+    let mut targets = Vec::new();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--wide" => wide = true,
+            "--limit" => {
+                let n = args.next().ok_or("--limit needs a number")?;
+                limit = n.parse().map_err(|_| format!("Bad limit: {n}"))?;
+            }
+            _ if arg.contains(',') => {
+                let target = arg
+                    .split_once(',')
+                    .and_then(|(x, y)| Some(Point::new(x.parse().ok()?, y.parse().ok()?)))
+                    .ok_or_else(|| format!("Bad target: {arg}, expected X,Y"))?;
+                targets.push(target);
+            }
+            _ if path.is_none() => path = Some(arg.as_str()),
+            _ => return Err(format!("Unexpected argument {arg}")),
+        }
+    }
+    if path.is_none() || targets.is_empty() {
+        return Err("Expected FILE and at least one X,Y target".to_owned());
+    }
+    let (input, params) = super::tool_input(year, path, &cli)?;
+    let map = input.split_once("\n\n").map_or(input.as_str(), |m| m.0);
+    let narrow = Warehouse::parse(map)?;
+    let start = match wide {
+        true => narrow.scaled(params.wide, params.tall),
+        false => narrow.clone(),
+    };
+    match plan(&start, &targets, limit)? {
+        Some(moves) => {
+            print!("{narrow}\n{}\n", super::show_moves(&moves));
+            eprintln!("{} moves", moves.len());
+            Ok(())
+        }
+        None => Err("Crates can't be pushed onto all the targets".to_owned()),
+    }
+}
+
+#[test]
+fn plan_works() {
+    let map = "#######\n#.....#\n#.O...#\n#..@..#\n#.....#\n#######";
+    let start = Warehouse::parse(map).unwrap();
+    let target = Point::new(4, 2);
+
+    let live = live_cells(&start, (1, 1), &[target]);
+    assert!(live.contains(&Point::new(2, 2)) && live.contains(&target));
+    // corners and cells along walls that don't lead to the target
+    assert!(!live.contains(&Point::new(1, 1)) && !live.contains(&Point::new(1, 2)));
+
+    let moves = plan(&start, &[target], 1000).unwrap().unwrap();
+    assert_eq!(super::show_moves(&moves), "<<^>>");
+
+    // output is a puzzle input
+    let (again, moves) = super::parse(&format!("{start}\n{}\n", super::show_moves(&moves)));
+    assert_eq!(again, start);
+    let mut wide = again.scaled(2, 1);
+    let target = Point::new(8, 2);
+    let pushes = plan(&wide, &[target], 1000).unwrap().unwrap();
+    assert!(pushes.len() > moves.len());
+    wide.run(&pushes, &Trace::default());
+    assert_eq!(wide.crates[0].pos, target);
+
+    // crates wider than two cells read back the same way
+    let wider = start.scaled(3, 1);
+    assert_eq!(Warehouse::parse(&wider.to_string()), Ok(wider));
+
+    // crate stuck in a corner, pruned without looking around
+    let stuck = Warehouse::parse("#####\n#O..#\n#..@#\n#####").unwrap();
+    assert_eq!(plan(&stuck, &[Point::new(2, 2)], 1), Ok(None));
+    assert!(plan(&start, &[Point::new(4, 2)], 3).is_err());
+}
@@ -0,0 +1,205 @@
+//! Playing a warehouse by hand from the terminal
+//!
+//! Keys are read straight from stdin, when it's a terminal `stty` switches off line buffering
+//! and echo for the duration so every key press counts right away.
+
+use std::{
+    io::{IsTerminal, Read, Write},
+    process::Command,
+};
+
+use super::{history::History, warehouse::Warehouse};
+use crate::{
+    runner::{Overrides, Year},
+    trace::Trace,
+    Point,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Key {
+    Move(Point),
+    Undo,
+    Redo,
+    Save,
+    Quit,
+}
+
+/// Keys from raw terminal input: `<>^v`, arrows or `hjkl` to move, anything unknown is
+/// skipped
+pub(super) fn keys(mut bytes: impl Iterator<Item = u8>) -> impl Iterator<Item = Key> {
+    std::iter::from_fn(move || loop {
+        let key = match bytes.next()? {
+            b'<' | b'h' => Key::Move(Point::L),
+            b'>' | b'l' => Key::Move(Point::R),
+            b'^' | b'k' => Key::Move(Point::U),
+            b'v' | b'j' => Key::Move(Point::D),
+            // arrows are ESC [ A..D
+            0x1b => match (bytes.next()?, bytes.next()?) {
+                (b'[', b'A') => Key::Move(Point::U),
+                (b'[', b'B') => Key::Move(Point::D),
+                (b'[', b'C') => Key::Move(Point::R),
+                (b'[', b'D') => Key::Move(Point::L),
+                _ => continue,
+            },
+            b'u' => Key::Undo,
+            b'r' => Key::Redo,
+            b'w' => Key::Save,
+            // ctrl-d
+            b'q' | 4 => Key::Quit,
+            _ => continue,
+        };
+        return Some(key);
+    })
+}
+
+pub(super) struct Play {
+    start: Warehouse,
+    pub history: History,
+}
+
+impl Play {
+    /// Moves from the input can be stepped through with redo until a new move is made
+    pub fn new(start: Warehouse, moves: &[Point]) -> Self {
+        Self {
+            history: History::replay(start.clone(), moves),
+            start,
+        }
+    }
+
+    /// Apply a key, `false` to stop playing
+    pub fn key(&mut self, key: Key) -> bool {
+        match key {
+            Key::Move(dir) => {
+                self.history.push(dir, &Trace::default());
+            }
+            Key::Undo => {
+                self.history.undo();
+            }
+            Key::Redo => {
+                self.history.redo();
+            }
+            Key::Save => {}
+            Key::Quit => return false,
+        }
+        true
+    }
+
+    /// Starting warehouse and moves made so far in puzzle format
+    pub fn puzzle(&self) -> String {
+        format!("{}\n{}\n", self.start, self.history.moves())
+    }
+
+    pub fn screen(&self, save: &str) -> String {
+        let h = &self.history;
+        let last = h.last().map_or("-".to_owned(), |d| d.to_string());
+        format!(
+            "\x1b[2J\x1b[H{}move {}/{}, last {last}, GPS {}\n\
+             <>^v, arrows or hjkl to move, u undo, r redo, w save to {save}, q quit\n",
+            h.warehouse,
+            h.position(),
+            h.len(),
+            h.warehouse.gps(),
+        )
+    }
+}
+
+/// Terminal without line buffering and echo for as long as this lives
+struct Raw(bool);
+
+impl Raw {
+    fn enter() -> Self {
+        let stty = |args: &[&str]| Command::new("stty").args(args).status();
+        Raw(std::io::stdin().is_terminal()
+            && stty(&["-icanon", "-echo"]).is_ok_and(|s| s.success()))
+    }
+}
+
+impl Drop for Raw {
+    fn drop(&mut self) {
+        if self.0 {
+            let _ = Command::new("stty").args(["icanon", "echo"]).status();
+        }
+    }
+}
+
+/// Play a warehouse from a file or the real input, moves in the input can be redone one by one
+pub(super) fn play(year: &Year, args: &[String]) -> Result<(), String> {
+    let (cli, args) = Overrides::from_args(args)?;
+    let mut path = None;
+    let mut save = "day15-moves.txt".to_owned();
+    let mut wide = false;
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--wide" => wide = true,
+            "--save" => save = args.next().ok_or("--save needs a file")?.clone(),
+            _ if path.is_none() => path = Some(arg.as_str()),
+            _ => return Err(format!("Unexpected argument {arg}")),
+        }
+    }
+    let (input, params) = super::tool_input(year, path, &cli)?;
+    let (map, moves) = input.split_once("\n\n").unwrap_or((&input, ""));
+    let mut start = Warehouse::parse(map)?;
+    if wide {
+        start = start.scaled(params.wide, params.tall);
+    }
+    let mut play = Play::new(start, &super::moves(moves));
+
+    let _raw = Raw::enter();
+    let mut stdout = std::io::stdout();
+    let mut message = String::new();
+    let bytes = std::io::stdin().lock().bytes().map_while(Result::ok);
+    let mut keys = keys(bytes);
+    loop {
+        write!(stdout, "{}{message}", play.screen(&save)).map_err(|e| e.to_string())?;
+        stdout.flush().map_err(|e| e.to_string())?;
+        let Some(key) = keys.next() else {
+            return Ok(());
+        };
+        if !play.key(key) {
+            return Ok(());
+        }
+        message = match key {
+            Key::Save => match std::fs::write(&save, play.puzzle()) {
+                Ok(()) => format!("saved {} moves to {save}\n", play.history.position()),
+                Err(err) => format!("can't save to {save}: {err}\n"),
+            },
+            _ => String::new(),
+        };
+    }
+}
+
+#[test]
+fn play_works() {
+    let input = "#######\n#...#.#\n#.....#\n#..OO@#\n#..O..#\n#.....#\n#######";
+    let mut play = Play::new(Warehouse::parse(input).unwrap().scaled(2, 1), &[Point::L]);
+
+    // redo the move from the input, then type the rest of the puzzle example
+    let typed = "r\x1b[Bv<<^u^^\x1b[Dxh^^q<";
+    let mut keys = keys(typed.bytes());
+    for key in keys.by_ref() {
+        if !play.key(key) {
+            break;
+        }
+    }
+    // everything after q is left alone
+    assert_eq!(keys.next(), Some(Key::Move(Point::L)));
+
+    let want = "\
+##############
+##...[].##..##
+##...@.[]...##
+##....[]....##
+##..........##
+##..........##
+##############
+";
+    assert_eq!(play.history.warehouse.to_string(), want);
+    assert_eq!(play.history.moves(), "<vv<<^^<<^^");
+
+    // saved puzzle plays back to the same place
+    let (map, moves) = super::parse(&play.puzzle());
+    let mut again = map.clone();
+    again.run(&moves, &Trace::default());
+    assert_eq!(again, play.history.warehouse);
+}
@@ -0,0 +1,357 @@
+//! Warehouse where crates are objects with a footprint
+//!
+//! Every crate is a rectangle of any size and the grid only records which crate covers a
+//! cell. A push starts at the cell in front of the robot and follows every crate it hits,
+//! checking the cells in front of each one, so chains of overlapping crates of different
+//! sizes move together or not at all, in any direction. Part 1 crates are 1×1, part 2 is the
+//! same warehouse scaled by 2×1.
+
+use std::fmt;
+
+use crate::{
+    trace::{Event, Level, Trace},
+    Point,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Crate {
+    /// Top left corner
+    pub pos: Point,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Crate {
+    pub fn cells(self) -> impl Iterator<Item = Point> {
+        (0..self.height)
+            .flat_map(move |dy| (0..self.width).map(move |dx| self.pos + Point { x: dx, y: dy }))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Tile {
+    Space,
+    Wall,
+    /// Index into [`Warehouse::crates`]
+    Crate(usize),
+}
+
+#[derive(Clone, PartialEq, Eq)]
+pub(super) struct Warehouse {
+    pub width: usize,
+    pub height: usize,
+    tiles: Vec<Tile>,
+    pub crates: Vec<Crate>,
+    pub robot: Point,
+}
+
+impl Warehouse {
+    /// Empty warehouse with the robot in the top left corner
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            tiles: vec![Tile::Space; width * height],
+            crates: Vec::new(),
+            robot: Point { x: 0, y: 0 },
+        }
+    }
+
+    /// Map in puzzle format, either `O` for small crates or `[]` for wide ones, `[==]` for
+    /// even wider ones
+    pub fn parse(map: &str) -> Result<Self, String> {
+        let rows = map.lines().collect::<Vec<_>>();
+        let width = rows.first().map_or(0, |r| r.len());
+        let mut w = Self::new(width, rows.len());
+        let mut robots = 0;
+        for (y, row) in rows.iter().enumerate() {
+            if row.len() != width {
+                return Err(format!("row {y} is {} wide, expected {width}", row.len()));
+            }
+            let row = row.as_bytes();
+            for (x, c) in row.iter().enumerate() {
+                let p = Point::new(x, y);
+                let small = |pos| Crate {
+                    pos,
+                    width: 1,
+                    height: 1,
+                };
+                match c {
+                    b'#' => w.tiles[x + y * width] = Tile::Wall,
+                    b'.' => {}
+                    b'@' => {
+                        w.robot = p;
+                        robots += 1;
+                    }
+                    b'O' => {
+                        w.add(small(p))?;
+                    }
+                    b'[' => {
+                        let inside = row[x + 1..].iter().take_while(|&&c| c == b'=').count();
+                        if row.get(x + inside + 1) != Some(&b']') {
+                            return Err(format!("crate at {p:?} is not closed"));
+                        }
+                        w.add(Crate {
+                            width: inside as i32 + 2,
+                            ..small(p)
+                        })?;
+                    }
+                    // covered by the crate that starts with `[`
+                    b'=' | b']' if w.tile(p) != Tile::Space => {}
+                    _ => return Err(format!("unexpected {:?} at {p:?}", *c as char)),
+                }
+            }
+        }
+        if robots != 1 {
+            return Err(format!("expected one robot, found {robots}"));
+        }
+        Ok(w)
+    }
+
+    fn index(&self, p: Point) -> Option<usize> {
+        let inside =
+            (0..self.width as i32).contains(&p.x) && (0..self.height as i32).contains(&p.y);
+        inside.then(|| p.x as usize + p.y as usize * self.width)
+    }
+
+    /// Everything outside of the warehouse is a wall
+    pub fn tile(&self, p: Point) -> Tile {
+        self.index(p).map_or(Tile::Wall, |ix| self.tiles[ix])
+    }
+
+    pub fn set_wall(&mut self, p: Point, wall: bool) {
+        if let Some(ix) = self.index(p) {
+            self.tiles[ix] = if wall { Tile::Wall } else { Tile::Space };
+        }
+    }
+
+    /// Place a crate, every cell it covers must be empty
+    pub fn add(&mut self, c: Crate) -> Result<usize, String> {
+        if let Some(p) = c.cells().find(|&p| self.tile(p) != Tile::Space) {
+            return Err(format!("{c:?} overlaps {:?} at {p:?}", self.tile(p)));
+        }
+        let id = self.crates.len();
+        self.crates.push(c);
+        self.stamp(id, Tile::Crate(id));
+        Ok(id)
+    }
+
+    fn stamp(&mut self, id: usize, tile: Tile) {
+        for p in self.crates[id].cells() {
+            let ix = self.index(p).expect("crates stay inside the warehouse");
+            self.tiles[ix] = tile;
+        }
+    }
+
+    /// Every cell becomes `wide` × `tall` cells, crates grow with it while the robot stays
+    /// the size of a single cell
+    pub fn scaled(&self, wide: usize, tall: usize) -> Self {
+        let (sx, sy) = (wide as i32, tall as i32);
+        let stretch = |p: Point| Point {
+            x: p.x * sx,
+            y: p.y * sy,
+        };
+        let mut w = Self::new(self.width * wide, self.height * tall);
+        for y in 0..w.height {
+            for x in 0..w.width {
+                let p = Point::new(x, y);
+                let from = Point {
+                    x: p.x / sx,
+                    y: p.y / sy,
+                };
+                w.set_wall(p, self.tile(from) == Tile::Wall);
+            }
+        }
+        for c in &self.crates {
+            w.add(Crate {
+                pos: stretch(c.pos),
+                width: c.width * sx,
+                height: c.height * sy,
+            })
+            .expect("scaled crates don't overlap");
+        }
+        w.robot = stretch(self.robot);
+        w
+    }
+
+    /// Crates the robot would push by moving in `dir`, `None` if something hits a wall
+    pub fn pushed(&self, dir: Point) -> Option<Vec<usize>> {
+        let mut moving = Vec::new();
+        let mut todo = vec![self.robot + dir];
+        while let Some(p) = todo.pop() {
+            match self.tile(p) {
+                Tile::Wall => return None,
+                Tile::Space => {}
+                Tile::Crate(id) if moving.contains(&id) => {}
+                Tile::Crate(id) => {
+                    moving.push(id);
+                    let ahead = self.crates[id].cells().map(|c| c + dir);
+                    todo.extend(ahead.filter(|&c| self.tile(c) != Tile::Crate(id)));
+                }
+            }
+        }
+        Some(moving)
+    }
+
+    /// Move the robot one step, pushing crates in front of it. Returns `false` if the move is
+    /// blocked and nothing changed.
+    pub fn push(&mut self, dir: Point, trace: &Trace) -> bool {
+        let Some(moving) = self.pushed(dir) else {
+            return false;
+        };
+        self.shift(&moving, dir, trace);
+        true
+    }
+
+    /// Move the robot and some crates by `dir`, whatever they move into must be empty or
+    /// moving too
+    pub fn shift(&mut self, crates: &[usize], dir: Point, trace: &Trace) {
+        for &id in crates {
+            self.stamp(id, Tile::Space);
+        }
+        for &id in crates {
+            let from = self.crates[id].pos;
+            trace.emit(Level::Trace, || Event::Move {
+                from: from.into(),
+                to: (from + dir).into(),
+            });
+            self.crates[id].pos = from + dir;
+            self.stamp(id, Tile::Crate(id));
+        }
+        self.robot = self.robot + dir;
+    }
+
+    /// Follow all the moves, emitting a frame before each one and after the last one
+    pub fn run(&mut self, moves: &[Point], trace: &Trace) {
+        for (step, &dir) in moves.iter().enumerate() {
+            trace.emit(Level::Debug, || Event::Frame { step, grid: &*self });
+            self.push(dir, trace);
+        }
+        let step = moves.len();
+        trace.emit(Level::Debug, || Event::Frame { step, grid: &*self });
+    }
+
+    /// Sum of GPS coordinates of all the crates, measured to their top left corner
+    pub fn gps(&self) -> usize {
+        let gps = self
+            .crates
+            .iter()
+            .map(|c| c.pos.x + c.pos.y * 100)
+            .sum::<i32>();
+        gps as usize
+    }
+}
+
+impl fmt::Display for Warehouse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let p = Point::new(x, y);
+                let c = match self.tile(p) {
+                    _ if p == self.robot => '@',
+                    Tile::Space => '.',
+                    Tile::Wall => '#',
+                    Tile::Crate(id) => {
+                        let c = self.crates[id];
+                        match p.x - c.pos.x {
+                            _ if c.width == 1 => 'O',
+                            0 => '[',
+                            dx if dx == c.width - 1 => ']',
+                            _ => '=',
+                        }
+                    }
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Same as [`fmt::Display`], on a fresh line for frames
+impl fmt::Debug for Warehouse {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f)?;
+        fmt::Display::fmt(self, f)
+    }
+}
+
+#[test]
+fn wide_pushes_work() {
+    let mut w = Warehouse::parse("#######\n#...#.#\n#.....#\n#..OO@#\n#..O..#\n#.....#\n#######")
+        .unwrap()
+        .scaled(2, 1);
+    for dir in [
+        Point::L,
+        Point::D,
+        Point::D,
+        Point::L,
+        Point::L,
+        Point::U,
+        Point::U,
+    ] {
+        w.push(dir, &Trace::default());
+    }
+    for dir in [Point::L, Point::L, Point::U, Point::U] {
+        w.push(dir, &Trace::default());
+    }
+    let want = "\
+##############
+##...[].##..##
+##...@.[]...##
+##....[]....##
+##..........##
+##..........##
+##############
+";
+    assert_eq!(w.to_string(), want);
+    assert_eq!(w.gps(), 105 + 207 + 306);
+}
+
+#[test]
+fn any_footprint_works() {
+    let trace = Trace::default();
+    let rect = |x, y, width, height| Crate {
+        pos: Point::new(x, y),
+        width,
+        height,
+    };
+
+    let mut w = Warehouse::new(8, 8);
+    let big = w.add(rect(2, 3, 3, 2)).unwrap();
+    let tall = w.add(rect(4, 1, 1, 2)).unwrap();
+    let small = w.add(rect(1, 6, 2, 1)).unwrap();
+    assert!(w.add(rect(3, 3, 1, 1)).is_err());
+    w.robot = Point::new(2, 7);
+
+    assert_eq!(w.pushed(Point::U), Some(vec![small]));
+    assert!(w.push(Point::U, &trace));
+    // small overlaps big by one column, big overlaps tall by one column
+    assert_eq!(w.pushed(Point::U), Some(vec![small, big, tall]));
+    assert!(w.push(Point::U, &trace));
+    // tall is against the edge now
+    assert_eq!(w.pushed(Point::U), None);
+    assert!(!w.push(Point::U, &trace));
+    assert_eq!(w.robot, Point::new(2, 5));
+    assert_eq!(w.crates[big].pos, Point::new(2, 2));
+
+    let mut w = Warehouse::new(8, 5);
+    let square = w.add(rect(2, 2, 2, 2)).unwrap();
+    let tall = w.add(rect(4, 1, 1, 3)).unwrap();
+    w.robot = Point::new(1, 3);
+    for _ in 0..3 {
+        assert_eq!(w.pushed(Point::R), Some(vec![square, tall]));
+        assert!(w.push(Point::R, &trace));
+    }
+    assert!(!w.push(Point::R, &trace));
+    let want = "\
+........
+.......O
+.....[]O
+....@[]O
+........
+";
+    assert_eq!(w.to_string(), want);
+    assert_eq!(w.gps(), 205 + 107);
+}
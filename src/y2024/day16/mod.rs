@@ -0,0 +1,236 @@
+//! Reindeer maze: Dijkstra over position and facing
+//!
+//! Every state is a tile along with the direction the reindeer faces. Moving costs whatever the
+//! tile moved onto costs plus the turn on the way, by default a step is 1, a 90° turn 1000 and
+//! turning around is not allowed. Alongside the distance to each state the search keeps every
+//! predecessor that reaches it at that distance, so the tiles on all the best paths come from
+//! walking that graph back from the end.
+
+mod yen;
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+};
+
+use crate::{
+    runner::{params, Solution, Tool},
+    trace::{Event, Level, Trace},
+    Point, TwoDee,
+};
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(16, 1, |i, p| {
+        part1(&parse(i), &p.get(), &p.trace).to_string()
+    }),
+    Solution::new(16, 2, |i, p| {
+        part2(&parse(i), &p.get(), &p.trace).to_string()
+    }),
+];
+
+pub(crate) const TOOLS: &[Tool] = &[Tool {
+    name: "paths",
+    usage: "[FILE] [K] [--param KEY=VALUE]...  The K cheapest day 16 paths drawn over the maze",
+    run: yen::tool,
+}];
+
+params! {
+    /// Cost of a step onto a tile when the maze comes without weights
+    step: usize = 1,
+    /// Cost of every 90° turn, 45° turns cost half of it rounded down
+    turn: usize = 1000,
+    /// Cost of turning around, 0 means it's not allowed
+    uturn: usize = 0,
+    /// Allow diagonal moves
+    diagonal: bool = false,
+}
+
+/// Clockwise starting from up, diagonals in between
+const DIRS: [Point; 8] = [
+    Point::U,
+    Point { x: 1, y: -1 },
+    Point::R,
+    Point { x: 1, y: 1 },
+    Point::D,
+    Point { x: -1, y: 1 },
+    Point::L,
+    Point { x: -1, y: -1 },
+];
+
+struct Maze {
+    walls: TwoDee<bool>,
+    /// Cost of stepping onto every tile, from a digit grid after the maze
+    weights: Option<TwoDee<usize>>,
+    start: Point,
+    end: Point,
+}
+
+/// Maze, optionally followed by a blank line and a grid of digits of the same size
+fn parse(input: &str) -> Maze {
+    let (maze, weights) = match input.split_once("\n\n") {
+        Some((maze, weights)) => (maze, Some(weights)),
+        None => (input, None),
+    };
+    #[derive(PartialEq)]
+    enum M {
+        Start,
+        End,
+        Wall,
+        Space,
+    }
+    let twodee = maze
+        .lines()
+        .map(|l| {
+            l.bytes().map(|c| match c {
+                b'#' => M::Wall,
+                b'S' => M::Start,
+                b'E' => M::End,
+                b'.' => M::Space,
+                _ => panic!(),
+            })
+        })
+        .collect::<TwoDee<M>>();
+    let weights = weights.map(|w| {
+        let weights = w
+            .lines()
+            .map(|l| l.bytes().map(|c| (c - b'0') as usize))
+            .collect::<TwoDee<usize>>();
+        assert_eq!(weights.width, twodee.width, "weights don't match the maze");
+        weights
+    });
+    let mut start = None;
+    let mut end = None;
+    for x in 0..twodee.width as i32 {
+        for y in 0..twodee.width as i32 {
+            let p = Point { x, y };
+            if twodee[p] == M::Start {
+                start = Some(p);
+            } else if twodee[p] == M::End {
+                end = Some(p);
+            }
+        }
+    }
+    Maze {
+        walls: twodee.map(|c| *c == M::Wall),
+        weights,
+        start: start.unwrap(),
+        end: end.unwrap(),
+    }
+}
+
+impl Params {
+    /// Cost of moving from `p` facing `dir` to the next tile in direction `d`, `None` if that
+    /// move isn't allowed
+    fn cost(&self, maze: &Maze, (p, dir): State, d: Point) -> Option<usize> {
+        let eighths = |d| DIRS.iter().position(|&e| e == d).unwrap();
+        let turn = (eighths(d) + 8 - eighths(dir)) % 8;
+        let turn = match turn.min(8 - turn) {
+            1 | 3 if !self.diagonal => return None,
+            4 if self.uturn == 0 => return None,
+            4 => self.uturn,
+            eighths => self.turn * eighths / 2,
+        };
+        if maze.walls[p + d] {
+            return None;
+        }
+        let step = maze.weights.as_ref().map_or(self.step, |w| w[p + d]);
+        Some(turn + step)
+    }
+}
+
+/// Position and the direction the reindeer faces
+type State = (Point, Point);
+
+/// States reachable from the start no more expensive than the end, with their distance and
+/// the states they are reached from at that distance
+struct Paths {
+    end: Point,
+    dist: HashMap<State, usize>,
+    prev: HashMap<State, Vec<State>>,
+}
+
+impl Paths {
+    fn new(maze: &Maze, costs: &Params, trace: &Trace) -> Self {
+        let (start, end) = (&maze.start, &maze.end);
+        let mut dist = HashMap::from([((*start, Point::R), 0)]);
+        let mut prev = HashMap::<State, Vec<State>>::new();
+        let mut queue = BinaryHeap::from([Reverse((0, (*start, Point::R)))]);
+        // anything costing more than reaching the end can't be on a best path
+        let mut done = usize::MAX;
+        while let Some(Reverse((cost, state))) = queue.pop() {
+            if cost > done {
+                break;
+            }
+            if dist[&state] < cost {
+                continue;
+            }
+            if state.0 == *end {
+                done = cost;
+            }
+            trace.emit(Level::Trace, || Event::Expand {
+                state: &state,
+                cost,
+            });
+            for d in DIRS {
+                let Some(step) = costs.cost(maze, state, d) else {
+                    continue;
+                };
+                let next = (state.0 + d, d);
+                let cost = cost + step;
+                match dist.get(&next) {
+                    Some(&known) if known < cost => {}
+                    Some(&known) if known == cost => prev.entry(next).or_default().push(state),
+                    _ => {
+                        dist.insert(next, cost);
+                        prev.insert(next, vec![state]);
+                        queue.push(Reverse((cost, next)));
+                    }
+                }
+            }
+        }
+        Self {
+            end: *end,
+            dist,
+            prev,
+        }
+    }
+
+    /// Cheapest way to reach the end facing any direction
+    fn best(&self) -> Option<usize> {
+        DIRS.iter()
+            .filter_map(|&d| self.dist.get(&(self.end, d)))
+            .min()
+            .copied()
+    }
+
+    /// Tiles on any of the cheapest paths
+    fn tiles(&self) -> HashSet<Point> {
+        let Some(best) = self.best() else {
+            return HashSet::new();
+        };
+        let mut todo = DIRS
+            .iter()
+            .map(|&d| (self.end, d))
+            .filter(|s| self.dist.get(s) == Some(&best))
+            .collect::<Vec<_>>();
+        let mut seen = todo.iter().copied().collect::<HashSet<_>>();
+        while let Some(state) = todo.pop() {
+            for &prev in self.prev.get(&state).into_iter().flatten() {
+                if seen.insert(prev) {
+                    todo.push(prev);
+                }
+            }
+        }
+        seen.into_iter().map(|(p, _)| p).collect()
+    }
+}
+
+fn part1(maze: &Maze, costs: &Params, trace: &Trace) -> usize {
+    Paths::new(maze, costs, trace)
+        .best()
+        .expect("Where are we?")
+}
+
+fn part2(maze: &Maze, costs: &Params, trace: &Trace) -> usize {
+    Paths::new(maze, costs, trace).tiles().len()
+}
@@ -0,0 +1,289 @@
+//! The k cheapest ways through the maze, Yen's algorithm on position and facing
+//!
+//! Every next path branches off one of the paths found so far: it follows that path to some
+//! spur state and then takes the cheapest way to the end that doesn't reuse a tile from before
+//! the spur and doesn't leave the spur the same way an already found path with the same start
+//! does. Distances to the end without any of those restrictions are computed once, backwards,
+//! and make every spur search an A* that goes more or less straight to the end. A new path is
+//! only spurred from where it branched off onwards.
+
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap, HashSet},
+    fmt,
+};
+
+use super::{Maze, Params, State, DIRS};
+use crate::{
+    runner::{Overrides, Year},
+    Point,
+};
+
+/// A way from the start to the end
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Path {
+    pub score: usize,
+    /// Every state along the way with its cost so far
+    pub states: Vec<(State, usize)>,
+}
+
+impl Path {
+    /// Moves made, one glyph per move
+    pub fn moves(&self) -> String {
+        self.states[1..]
+            .iter()
+            .map(|&((_, d), _)| glyph(d))
+            .collect()
+    }
+
+    /// Maze with the path drawn over it, every tile shows the direction it was entered in
+    pub fn render(&self, maze: &Maze) -> String {
+        let on = self.states[1..]
+            .iter()
+            .map(|&((p, d), _)| (p, glyph(d)))
+            .collect::<HashMap<_, _>>();
+        let mut out = String::new();
+        for y in 0..maze.walls.width as i32 {
+            for x in 0..maze.walls.width as i32 {
+                let p = Point { x, y };
+                out.push(match on.get(&p) {
+                    _ if p == maze.start => 'S',
+                    _ if p == maze.end => 'E',
+                    Some(&c) => c,
+                    None if maze.walls[p] => '#',
+                    None => '.',
+                });
+            }
+            out.push('\n');
+        }
+        out
+    }
+}
+
+impl fmt::Display for Path {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "score {}, {} moves", self.score, self.states.len() - 1)
+    }
+}
+
+fn glyph(d: Point) -> char {
+    match (d.x, d.y) {
+        (0, -1) => '^',
+        (1, 0) => '>',
+        (0, 1) => 'v',
+        (-1, 0) => '<',
+        (1, -1) | (-1, 1) => '/',
+        _ => '\\',
+    }
+}
+
+struct Search<'a> {
+    maze: &'a Maze,
+    costs: &'a Params,
+    /// Cheapest way to the end from every state that has one
+    to_end: HashMap<State, usize>,
+}
+
+impl<'a> Search<'a> {
+    fn new(maze: &'a Maze, costs: &'a Params) -> Self {
+        let ends = DIRS.map(|d| (maze.end, d));
+        let mut to_end = ends.iter().map(|&s| (s, 0)).collect::<HashMap<_, _>>();
+        let mut queue = ends
+            .map(|s| Reverse((0, s)))
+            .into_iter()
+            .collect::<BinaryHeap<_>>();
+        while let Some(Reverse((cost, (p, d)))) = queue.pop() {
+            if to_end[&(p, d)] < cost || maze.walls[p - d] {
+                continue;
+            }
+            for dir in DIRS {
+                let prev = (p - d, dir);
+                let Some(step) = costs.cost(maze, prev, d) else {
+                    continue;
+                };
+                let cost = cost + step;
+                if to_end.get(&prev).is_none_or(|&known| known > cost) {
+                    to_end.insert(prev, cost);
+                    queue.push(Reverse((cost, prev)));
+                }
+            }
+        }
+        Self {
+            maze,
+            costs,
+            to_end,
+        }
+    }
+
+    /// Cheapest way to the end from `from`, staying off `blocked` tiles and not making any of
+    /// the `banned` moves
+    fn spur(
+        &self,
+        from: (State, usize),
+        blocked: &HashSet<Point>,
+        banned: &HashSet<(State, Point)>,
+    ) -> Option<Vec<(State, usize)>> {
+        let mut cost = HashMap::from([from]);
+        let mut prev = HashMap::new();
+        // on ties the state closest to the end goes first, with an exact estimate that's most
+        // of them
+        let f = from.1 + *self.to_end.get(&from.0)?;
+        let mut queue = BinaryHeap::from([Reverse((f, Reverse(from.1), from.0))]);
+        while let Some(Reverse((_, Reverse(g), state))) = queue.pop() {
+            if cost[&state] < g {
+                continue;
+            }
+            if state.0 == self.maze.end {
+                let mut path = vec![(state, g)];
+                while let Some(&p) = prev.get(&path[path.len() - 1].0) {
+                    path.push((p, cost[&p]));
+                }
+                path.reverse();
+                return Some(path);
+            }
+            for d in DIRS {
+                let next = (state.0 + d, d);
+                if blocked.contains(&next.0) || banned.contains(&(state, d)) {
+                    continue;
+                }
+                let (Some(step), Some(h)) =
+                    (self.costs.cost(self.maze, state, d), self.to_end.get(&next))
+                else {
+                    continue;
+                };
+                let g = g + step;
+                if cost.get(&next).is_none_or(|&known| known > g) {
+                    cost.insert(next, g);
+                    prev.insert(next, state);
+                    queue.push(Reverse((g + h, Reverse(g), next)));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Up to `k` cheapest paths without repeated tiles from the start to the end, cheapest first
+pub(super) fn best_paths(maze: &Maze, costs: &Params, k: usize) -> Vec<Path> {
+    let search = Search::new(maze, costs);
+    let path = |states: Vec<(State, usize)>| Path {
+        score: states[states.len() - 1].1,
+        states,
+    };
+    if k == 0 {
+        return Vec::new();
+    }
+    let Some(first) = search.spur(
+        ((maze.start, Point::R), 0),
+        &HashSet::new(),
+        &HashSet::new(),
+    ) else {
+        return Vec::new();
+    };
+    let mut found = vec![path(first)];
+    // where the last path branched off, spurring any earlier would repeat what was done for
+    // the path it branched off from
+    let mut branch = 0;
+    let mut candidates = BinaryHeap::new();
+    let mut seen = HashSet::new();
+    while found.len() < k {
+        let last = &found[found.len() - 1];
+        for j in branch..last.states.len() - 1 {
+            let root = &last.states[..=j];
+            let banned = found
+                .iter()
+                .filter(|p| p.states.len() > j + 1 && p.states[..=j] == *root)
+                .map(|p| (root[j].0, p.states[j + 1].0 .1))
+                .collect::<HashSet<_>>();
+            let blocked = root[..j]
+                .iter()
+                .map(|((p, _), _)| *p)
+                .collect::<HashSet<_>>();
+            let Some(spur) = search.spur(root[j], &blocked, &banned) else {
+                continue;
+            };
+            let states = [&root[..j], &spur[..]].concat();
+            if seen.insert(states.clone()) {
+                candidates.push(Reverse((states[states.len() - 1].1, states, j)));
+            }
+        }
+        let Some(Reverse((_, states, j))) = candidates.pop() else {
+            break;
+        };
+        branch = j;
+        found.push(path(states));
+    }
+    found
+}
+
+/// Print the k cheapest paths through a maze drawn over it, costs come from the file header
+/// and `--param`
+pub(super) fn tool(year: &Year, args: &[String]) -> Result<(), String> {
+    let (cli, args) = Overrides::from_args(args)?;
+    year.check_params(&[16], &cli)?;
+    let (path, k) = match args.as_slice() {
+        [] => (None, "3"),
+        [k] if k.parse::<usize>().is_ok() => (None, k.as_str()),
+        [path] => (Some(path.as_str()), "3"),
+        [path, k] => (Some(path.as_str()), k.as_str()),
+        _ => return Err("Expected [FILE] [K]".to_owned()),
+    };
+    let k = k.parse().map_err(|_| format!("Bad number of paths: {k}"))?;
+    if k == 0 {
+        return Err("Expected at least one path".to_owned());
+    }
+    let (input, mut params) = year.tool_input(16, path)?;
+    params.extend(&cli);
+    let maze = super::parse(&input);
+    let paths = best_paths(&maze, &params.try_get()?, k);
+    if paths.is_empty() {
+        return Err("No way to the end".to_owned());
+    }
+    for (n, path) in paths.iter().enumerate() {
+        println!(
+            "#{}: {path}\n{}{}\n",
+            n + 1,
+            path.render(&maze),
+            path.moves()
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn best_paths_work() {
+    let year = &crate::y2024::YEAR;
+    let maze = super::parse(&year.example("day16").input);
+    let paths = best_paths(&maze, &Params::default(), 5);
+    let scores = paths.iter().map(|p| p.score).collect::<Vec<_>>();
+    // three ways to tie for the best, then the cheapest of the rest
+    assert_eq!(scores[..3], [7036; 3]);
+    assert!(scores[3] > 7036 && scores[3] <= scores[4]);
+    let tiles = paths[..3]
+        .iter()
+        .flat_map(|p| p.states.iter().map(|((t, _), _)| *t))
+        .collect::<HashSet<_>>();
+    assert_eq!(tiles.len(), 45);
+    for p in &paths {
+        assert_eq!(
+            p.states
+                .iter()
+                .map(|((t, _), _)| t)
+                .collect::<HashSet<_>>()
+                .len(),
+            p.states.len()
+        );
+    }
+
+    assert!(best_paths(&maze, &Params::default(), 0).is_empty());
+
+    let maze = super::parse("#####\n#..E#\n#...#\n#S..#\n#####");
+    let paths = best_paths(&maze, &Params::default(), 2);
+    assert_eq!(paths[0].moves(), ">>^^");
+    assert_eq!(paths[0].score, 1004);
+    assert_eq!(
+        paths[1].render(&maze),
+        "#####\n#^>E#\n#^..#\n#S..#\n#####\n"
+    );
+    assert_eq!(paths[1].to_string(), "score 2004, 4 moves");
+}
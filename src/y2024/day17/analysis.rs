@@ -0,0 +1,495 @@
+//! Control flow and dataflow of `Puter` programs
+//!
+//! Programs are split into basic blocks at `jnz` instructions and their targets, loops are
+//! found from back edges: a jump to a block that dominates the jump. For every loop the
+//! analysis works out which registers carry a value from one iteration to the next, which
+//! ones are overwritten on every iteration, and how far A is shifted and how many digits come
+//! out per iteration, if that's the same on every path. Jumps go where [`Puter::step`] sends
+//! them, odd targets included.
+//!
+//! [`Puter::step`]: super::Puter
+
+use std::fmt;
+
+use super::{CoOp, Instr, Program};
+use crate::runner::Year;
+
+/// Set of registers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct Regs(u8);
+
+impl Regs {
+    pub const NONE: Regs = Regs(0);
+    pub const A: Regs = Regs(1);
+    pub const B: Regs = Regs(2);
+    pub const C: Regs = Regs(4);
+
+    fn of(op: CoOp) -> Regs {
+        match op {
+            CoOp::Lit(_) => Regs::NONE,
+            CoOp::A => Regs::A,
+            CoOp::B => Regs::B,
+            CoOp::C => Regs::C,
+        }
+    }
+
+    pub fn contains(self, other: Regs) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    fn union(self, other: Regs) -> Regs {
+        Regs(self.0 | other.0)
+    }
+
+    fn minus(self, other: Regs) -> Regs {
+        Regs(self.0 & !other.0)
+    }
+
+    fn intersect(self, other: Regs) -> Regs {
+        Regs(self.0 & other.0)
+    }
+}
+
+impl fmt::Display for Regs {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names = [(Regs::A, "A"), (Regs::B, "B"), (Regs::C, "C")]
+            .iter()
+            .filter(|(r, _)| self.contains(*r))
+            .map(|(_, name)| *name)
+            .collect::<Vec<_>>();
+        match names.is_empty() {
+            true => write!(f, "-"),
+            false => write!(f, "{}", names.join(" ")),
+        }
+    }
+}
+
+/// Registers an instruction reads and writes
+fn uses(instr: Instr) -> (Regs, Regs) {
+    match instr {
+        Instr::Adv(op) => (Regs::A.union(Regs::of(op)), Regs::A),
+        Instr::Bxl(_) => (Regs::B, Regs::B),
+        Instr::Bst(op) => (Regs::of(op), Regs::B),
+        Instr::Jnz(_) => (Regs::A, Regs::NONE),
+        Instr::Bxc(_) => (Regs::B.union(Regs::C), Regs::B),
+        Instr::Out(op) => (Regs::of(op), Regs::NONE),
+        Instr::Bdv(op) => (Regs::A.union(Regs::of(op)), Regs::B),
+        Instr::Cdv(op) => (Regs::A.union(Regs::of(op)), Regs::C),
+    }
+}
+
+/// Instructions `start..end`, only the last one can be a jump
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Block {
+    pub start: usize,
+    pub end: usize,
+    pub succs: Vec<usize>,
+    /// Program can halt after this block
+    pub exits: bool,
+}
+
+/// What one iteration of a loop does, `None` when it depends on the path taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Iteration {
+    /// Total of `adv` shifts
+    pub shift: Option<usize>,
+    pub outputs: Option<usize>,
+}
+
+impl Iteration {
+    fn merge(self, other: Iteration) -> Iteration {
+        let same = |x: Option<usize>, y: Option<usize>| if x == y { x } else { None };
+        Iteration {
+            shift: same(self.shift, other.shift),
+            outputs: same(self.outputs, other.outputs),
+        }
+    }
+
+    fn then(self, instr: Instr) -> Iteration {
+        match instr {
+            Instr::Adv(CoOp::Lit(x)) => Iteration {
+                shift: self.shift.map(|s| s + x),
+                ..self
+            },
+            Instr::Adv(_) => Iteration {
+                shift: None,
+                ..self
+            },
+            Instr::Out(_) => Iteration {
+                outputs: self.outputs.map(|o| o + 1),
+                ..self
+            },
+            _ => self,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Loop {
+    pub head: usize,
+    /// Blocks that jump back to `head`
+    pub latches: Vec<usize>,
+    pub blocks: Vec<usize>,
+    /// Registers whose value from before the iteration is used
+    pub live: Regs,
+    /// Registers written on every path through the loop
+    pub overwritten: Regs,
+    pub iteration: Iteration,
+}
+
+#[derive(Debug, Clone)]
+pub(super) struct Analysis {
+    pub blocks: Vec<Block>,
+    pub loops: Vec<Loop>,
+    /// Registers whose initial value is used
+    pub inputs: Regs,
+}
+
+impl Analysis {
+    pub fn new(code: &Program) -> Self {
+        let blocks = blocks(code);
+        let live = liveness(code, &blocks);
+        let doms = dominators(&blocks);
+
+        let mut loops = Vec::<Loop>::new();
+        for (from, block) in blocks.iter().enumerate() {
+            for &head in &block.succs {
+                if !doms[from].contains(&head) {
+                    continue;
+                }
+                match loops.iter_mut().find(|l| l.head == head) {
+                    Some(l) => l.latches.push(from),
+                    None => loops.push(Loop {
+                        head,
+                        latches: vec![from],
+                        blocks: Vec::new(),
+                        live: live[head],
+                        overwritten: Regs::NONE,
+                        iteration: Iteration {
+                            shift: None,
+                            outputs: None,
+                        },
+                    }),
+                }
+            }
+        }
+        for l in &mut loops {
+            l.blocks = body(&blocks, l);
+            (l.overwritten, l.iteration) = iteration(code, &blocks, l);
+        }
+        loops.sort_by_key(|l| l.head);
+
+        Self {
+            inputs: live.first().copied().unwrap_or_default(),
+            blocks,
+            loops,
+        }
+    }
+
+    /// Check that building A backwards from the last digit works for this program: it's a
+    /// single loop over the whole program that outputs one digit and shifts A by 3 bits per
+    /// iteration, and B and C are computed from A from scratch every time. For programs like
+    /// this output digit `k` depends only on `A >> 3k`.
+    pub fn backwards(&self, code: &Program) -> Result<(), String> {
+        let jumps = code.0.iter().filter(|i| matches!(i, Instr::Jnz(_))).count();
+        let [l] = &self.loops[..] else {
+            return Err(format!(
+                "program must be a single loop, has {}",
+                self.loops.len()
+            ));
+        };
+        let last = self.blocks.len() - 1;
+        if jumps != 1 || l.head != 0 || l.latches != [last] || l.blocks.len() != self.blocks.len() {
+            return Err("program must be a single loop ending with jnz 0".to_owned());
+        }
+        if l.iteration.outputs != Some(1) {
+            return Err("program must output exactly one digit per iteration".to_owned());
+        }
+        let advs = code.0.iter().filter(|i| matches!(i, Instr::Adv(_)));
+        if l.iteration.shift != Some(3) || advs.count() != 1 {
+            return Err("program must shift A by 3 bits once per iteration".to_owned());
+        }
+        let carried = l.live.minus(Regs::A);
+        if carried != Regs::NONE {
+            return Err(format!("{carried} carried over from a previous iteration"));
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let addrs = |bs: &[usize]| {
+            let bs = bs.iter().map(|&b| (self.blocks[b].start * 2).to_string());
+            bs.collect::<Vec<_>>().join(" ")
+        };
+        writeln!(f, "initial values used: {}", self.inputs)?;
+        for b in &self.blocks {
+            let exit = if b.exits { " halt" } else { "" };
+            writeln!(
+                f,
+                "block {}..{} -> {}{exit}",
+                b.start * 2,
+                b.end * 2,
+                addrs(&b.succs)
+            )?;
+        }
+        let show = |x: Option<usize>| x.map_or("varies".to_owned(), |x| x.to_string());
+        for l in &self.loops {
+            writeln!(
+                f,
+                "loop at {}: blocks {}, latches {}, live {}, overwritten {}, \
+                 A shifted by {} bits, {} digits per iteration",
+                self.blocks[l.head].start * 2,
+                addrs(&l.blocks),
+                addrs(&l.latches),
+                l.live,
+                l.overwritten,
+                show(l.iteration.shift),
+                show(l.iteration.outputs),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Split the program into basic blocks
+fn blocks(code: &Program) -> Vec<Block> {
+    let len = code.0.len();
+    let mut starts = vec![false; len + 1];
+    starts[0] = true;
+    for (ix, instr) in code.0.iter().enumerate() {
+        if let Instr::Jnz(target) = instr {
+            starts[ix + 1] = true;
+            if target / 2 < len {
+                starts[target / 2] = true;
+            }
+        }
+    }
+    let bounds = (0..=len).filter(|&ix| starts[ix]).collect::<Vec<_>>();
+    let block_at = |ix: usize| bounds.iter().position(|&s| s == ix);
+
+    bounds
+        .windows(2)
+        .chain(std::iter::once(&[bounds[bounds.len() - 1], len][..]))
+        .filter(|w| w[0] < w[1])
+        .map(|w| {
+            let (start, end) = (w[0], w[1]);
+            let mut succs = Vec::new();
+            let mut exits = false;
+            let mut go = |ix: usize| match block_at(ix).filter(|_| ix < len) {
+                Some(b) if !succs.contains(&b) => succs.push(b),
+                Some(_) => {}
+                None => exits = true,
+            };
+            match code.0[end - 1] {
+                Instr::Jnz(target) => {
+                    go(end);
+                    go(target / 2);
+                }
+                _ => go(end),
+            }
+            Block {
+                start,
+                end,
+                succs,
+                exits,
+            }
+        })
+        .collect()
+}
+
+/// Registers live at the start of each block
+fn liveness(code: &Program, blocks: &[Block]) -> Vec<Regs> {
+    let mut live = vec![Regs::NONE; blocks.len()];
+    loop {
+        let mut changed = false;
+        for (ix, b) in blocks.iter().enumerate().rev() {
+            let mut regs = b.succs.iter().fold(Regs::NONE, |r, &s| r.union(live[s]));
+            for &instr in code.0[b.start..b.end].iter().rev() {
+                let (reads, writes) = uses(instr);
+                regs = regs.minus(writes).union(reads);
+            }
+            changed |= regs != live[ix];
+            live[ix] = regs;
+        }
+        if !changed {
+            return live;
+        }
+    }
+}
+
+/// Blocks that dominate each block: every path from the start to it goes through them
+fn dominators(blocks: &[Block]) -> Vec<Vec<usize>> {
+    if blocks.is_empty() {
+        return Vec::new();
+    }
+    let all = (0..blocks.len()).collect::<Vec<_>>();
+    let mut doms = vec![all; blocks.len()];
+    doms[0] = vec![0];
+    loop {
+        let mut changed = false;
+        for ix in 1..blocks.len() {
+            let preds = (0..blocks.len())
+                .filter(|&p| blocks[p].succs.contains(&ix))
+                .collect::<Vec<_>>();
+            let mut new = match preds.split_first() {
+                Some((&first, rest)) => doms[first]
+                    .iter()
+                    .copied()
+                    .filter(|d| rest.iter().all(|&p| doms[p].contains(d)))
+                    .collect(),
+                None => Vec::new(),
+            };
+            if !new.contains(&ix) {
+                new.push(ix);
+                new.sort();
+            }
+            changed |= new != doms[ix];
+            doms[ix] = new;
+        }
+        if !changed {
+            return doms;
+        }
+    }
+}
+
+/// Natural loop: the head and everything that reaches a latch without going through the head
+fn body(blocks: &[Block], l: &Loop) -> Vec<usize> {
+    let mut body = vec![l.head];
+    let mut todo = l.latches.clone();
+    while let Some(b) = todo.pop() {
+        if body.contains(&b) {
+            continue;
+        }
+        body.push(b);
+        todo.extend((0..blocks.len()).filter(|&p| blocks[p].succs.contains(&b)));
+    }
+    body.sort();
+    body
+}
+
+/// Registers written on every path from the head to a latch, and what the paths do
+fn iteration(code: &Program, blocks: &[Block], l: &Loop) -> (Regs, Iteration) {
+    // state at the end of each block, `None` until some path reaches it
+    let mut ends = vec![None::<(Regs, Iteration)>; blocks.len()];
+    let start = Iteration {
+        shift: Some(0),
+        outputs: Some(0),
+    };
+    loop {
+        let mut changed = false;
+        for &b in &l.blocks {
+            let entry = if b == l.head {
+                Some((Regs::NONE, start))
+            } else {
+                l.blocks
+                    .iter()
+                    .filter(|&&p| blocks[p].succs.contains(&b))
+                    .filter_map(|&p| ends[p])
+                    .reduce(|(r1, i1), (r2, i2)| (r1.intersect(r2), i1.merge(i2)))
+            };
+            let Some((mut regs, mut it)) = entry else {
+                continue;
+            };
+            for &instr in &code.0[blocks[b].start..blocks[b].end] {
+                regs = regs.union(uses(instr).1);
+                it = it.then(instr);
+            }
+            // paths through an inner loop can go around any number of times
+            let end = match ends[b] {
+                Some((old_regs, old)) => (old_regs.intersect(regs), old.merge(it)),
+                None => (regs, it),
+            };
+            changed |= ends[b] != Some(end);
+            ends[b] = Some(end);
+        }
+        if !changed {
+            break;
+        }
+    }
+    l.latches
+        .iter()
+        .filter_map(|&b| ends[b])
+        .reduce(|(r1, i1), (r2, i2)| (r1.intersect(r2), i1.merge(i2)))
+        .unwrap_or((Regs::NONE, start))
+}
+
+/// Print control flow and dataflow of a program from a file or the real input
+pub(super) fn analyze(year: &Year, args: &[String]) -> Result<(), String> {
+    let code = super::parse(&super::source(year, args)?).code;
+    let analysis = Analysis::new(&code);
+    print!("{analysis}");
+    match analysis.backwards(&code) {
+        Ok(()) => println!("backwards search applies"),
+        Err(err) => println!("backwards search doesn't apply: {err}"),
+    }
+    Ok(())
+}
+
+#[test]
+fn analysis_works() {
+    let analyze = |src: &str| {
+        let code = Program::assemble(src).unwrap();
+        (Analysis::new(&code), code)
+    };
+
+    let (a, code) = analyze("bst A\nbxl 7\ncdv B\nadv 3\nbxc\nout B\njnz 0");
+    assert_eq!(a.blocks.len(), 1);
+    assert_eq!(
+        (a.blocks[0].succs.clone(), a.blocks[0].exits),
+        (vec![0], true)
+    );
+    let l = &a.loops[0];
+    assert_eq!((l.live, l.overwritten), (Regs::A, Regs(7)));
+    assert_eq!(
+        l.iteration,
+        Iteration {
+            shift: Some(3),
+            outputs: Some(1)
+        }
+    );
+    assert!(a.backwards(&code).is_ok());
+
+    // B is set once before the loop and carried through it
+    let (a, code) = analyze("bst A\nloop: bxl 1\nout B\nadv 3\njnz loop");
+    assert_eq!(a.blocks.len(), 2);
+    assert_eq!(a.inputs, Regs::A);
+    let l = &a.loops[0];
+    assert_eq!(
+        (l.head, l.live, l.overwritten),
+        (1, Regs::A.union(Regs::B), Regs(3))
+    );
+    assert!(a.backwards(&code).is_err());
+
+    // the inner loop goes around any number of times for every outer iteration
+    let (a, code) = analyze("outer: bst A\ninner: out B\nadv 1\njnz inner\nbdv 2\njnz outer");
+    assert_eq!(a.loops.iter().map(|l| l.head).collect::<Vec<_>>(), [0, 1]);
+    let (outer, inner) = (&a.loops[0], &a.loops[1]);
+    assert_eq!(
+        (outer.blocks.clone(), inner.blocks.clone()),
+        (vec![0, 1, 2], vec![1])
+    );
+    assert_eq!(
+        (outer.iteration.shift, outer.iteration.outputs),
+        (None, None)
+    );
+    assert_eq!(
+        (inner.iteration.shift, inner.iteration.outputs),
+        (Some(1), Some(1))
+    );
+    assert_eq!((outer.overwritten, inner.overwritten), (Regs(3), Regs::A));
+    assert_eq!(
+        a.backwards(&code),
+        Err("program must be a single loop, has 2".to_owned())
+    );
+
+    // jnz 7 goes past the end of the program and halts
+    let a = Analysis::new(&"0,1,3,7,5,4".parse().unwrap());
+    assert!(a.loops.is_empty());
+    assert_eq!((a.blocks.len(), a.blocks[0].exits), (2, true));
+
+    // nothing to run, nothing to find
+    let a = Analysis::new(&Program::assemble("").unwrap());
+    assert!(a.blocks.is_empty() && a.loops.is_empty());
+}
@@ -0,0 +1,226 @@
+//! Byte accurate execution
+//!
+//! [`super::Program`] decodes instructions two bytes at a time, which is all the real inputs
+//! need. The puzzle itself only talks about bytes: a jump can land on an odd address so an
+//! operand gets executed as an opcode, a program can have an odd length, and combo operand 7 is
+//! only a problem once an instruction actually uses it. [`Vm`] decodes at every step from the
+//! raw bytes and works for all of those.
+
+use std::fmt;
+
+use crate::runner::Year;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct Vm {
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
+    /// Byte address
+    pub ip: usize,
+    pub bytes: Vec<u8>,
+}
+
+/// Why the program stopped, along with `ip` at that moment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Halt {
+    /// Tried to read an opcode past the end of the program, this is the normal way to stop
+    End { ip: usize },
+    /// Opcode is the last byte of the program, there's no operand to read
+    NoOperand { ip: usize },
+    /// Instruction uses combo operand 7, which is reserved
+    Reserved { ip: usize, opcode: u8 },
+}
+
+impl fmt::Display for Halt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Halt::End { ip } => write!(f, "halted at {ip}, past the end of the program"),
+            Halt::NoOperand { ip } => write!(f, "halted at {ip}, opcode without an operand"),
+            Halt::Reserved { ip, opcode } => {
+                write!(
+                    f,
+                    "invalid program: opcode {opcode} at {ip} uses combo operand 7"
+                )
+            }
+        }
+    }
+}
+
+/// What a single instruction did
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Tick {
+    Ran,
+    Out(u8),
+    Halt(Halt),
+}
+
+/// `a / 2^x`, shifting everything out for large `x` instead of overflowing
+pub(super) fn shr(a: usize, x: usize) -> usize {
+    u32::try_from(x)
+        .ok()
+        .and_then(|x| a.checked_shr(x))
+        .unwrap_or(0)
+}
+
+impl Vm {
+    /// Registers and program in puzzle format. Unlike [`super::parse`], any sequence of 3 bit
+    /// numbers is a valid program.
+    pub fn parse(input: &str) -> Result<Self, String> {
+        let mut regs = [0; 3];
+        let mut bytes = None;
+        for line in input.lines() {
+            if let Some(program) = line.strip_prefix("Program:") {
+                let program = program
+                    .split(',')
+                    .map(|b| match b.trim().parse::<u8>() {
+                        Ok(b) if b < 8 => Ok(b),
+                        _ => Err(format!("expected a number between 0 and 7, got {b:?}")),
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                bytes = Some(program);
+            } else if let Some((reg, value)) = line
+                .strip_prefix("Register ")
+                .and_then(|l| l.split_once(':'))
+            {
+                let ix = ["A", "B", "C"]
+                    .iter()
+                    .position(|r| *r == reg)
+                    .ok_or_else(|| format!("unknown register {reg}"))?;
+                regs[ix] = value.trim().parse().map_err(|e| format!("{reg}: {e}"))?;
+            }
+        }
+        let [a, b, c] = regs;
+        Ok(Self {
+            a,
+            b,
+            c,
+            ip: 0,
+            bytes: bytes.ok_or("no Program: line")?,
+        })
+    }
+
+    fn combo(&self, op: u8) -> Option<usize> {
+        match op {
+            0..=3 => Some(op as usize),
+            4 => Some(self.a),
+            5 => Some(self.b),
+            6 => Some(self.c),
+            _ => None,
+        }
+    }
+
+    /// Decode and execute instruction at `ip`, registers and `ip` are left alone if it can't
+    /// be executed
+    pub fn step(&mut self) -> Tick {
+        let ip = self.ip;
+        let Some(&opcode) = self.bytes.get(ip) else {
+            return Tick::Halt(Halt::End { ip });
+        };
+        let Some(&op) = self.bytes.get(ip + 1) else {
+            return Tick::Halt(Halt::NoOperand { ip });
+        };
+        let combo = match (opcode, self.combo(op)) {
+            (1 | 3 | 4, _) => 0,
+            (_, Some(x)) => x,
+            (_, None) => return Tick::Halt(Halt::Reserved { ip, opcode }),
+        };
+        self.ip += 2;
+        match opcode {
+            0 => self.a = shr(self.a, combo),
+            1 => self.b ^= op as usize,
+            2 => self.b = combo % 8,
+            3 => {
+                if self.a != 0 {
+                    self.ip = op as usize;
+                }
+            }
+            4 => self.b ^= self.c,
+            5 => return Tick::Out((combo % 8) as u8),
+            6 => self.b = shr(self.a, combo),
+            7 => self.c = shr(self.a, combo),
+            _ => unreachable!("bytes are checked when parsing"),
+        }
+        Tick::Ran
+    }
+
+    /// Run until the program stops, or for at most `max_steps` instructions
+    pub fn run(&mut self, out: &mut Vec<u8>, max_steps: usize) -> Option<Halt> {
+        for _ in 0..max_steps {
+            match self.step() {
+                Tick::Ran => {}
+                Tick::Out(digit) => out.push(digit),
+                Tick::Halt(halt) => return Some(halt),
+            }
+        }
+        None
+    }
+}
+
+/// Same as [`super::part1`] for any program, errors say why a program has no answer: it
+/// doesn't parse, uses a reserved operand or doesn't stop
+pub(super) fn part1(input: &str) -> Result<String, String> {
+    let mut vm = Vm::parse(input)?;
+    let mut out = Vec::new();
+    match vm.run(&mut out, super::MAX_STEPS) {
+        Some(Halt::End { .. } | Halt::NoOperand { .. }) => Ok(super::join(&out)),
+        Some(halt) => Err(halt.to_string()),
+        None => Err(format!("still running after {} steps", super::MAX_STEPS)),
+    }
+}
+
+/// Run a program byte by byte, print output and how it stopped
+pub(super) fn exec(year: &Year, args: &[String]) -> Result<(), String> {
+    let mut vm = Vm::parse(&super::source(year, args)?)?;
+    let mut out = Vec::new();
+    let halt = vm.run(&mut out, super::MAX_STEPS);
+    println!("{}", super::join(&out));
+    match halt {
+        Some(halt) => {
+            println!("{halt}");
+            Ok(())
+        }
+        None => Err(format!(
+            "still running after {} steps at {}",
+            super::MAX_STEPS,
+            vm.ip
+        )),
+    }
+}
+
+#[cfg(test)]
+fn vm(program: &str, a: usize) -> (String, Option<Halt>) {
+    let input = format!("Register A: {a}\nRegister B: 0\nRegister C: 0\n\nProgram: {program}");
+    let mut vm = Vm::parse(&input).unwrap();
+    let mut out = Vec::new();
+    let halt = vm.run(&mut out, 1000);
+    (super::join(&out), halt)
+}
+
+#[test]
+fn vm_works() {
+    let end = |ip| Some(Halt::End { ip });
+    assert_eq!(
+        vm("0,1,5,4,3,0", 729),
+        ("4,6,3,5,6,3,5,2,1,0".to_owned(), end(6))
+    );
+    // odd length, last byte is an opcode without an operand
+    assert_eq!(
+        vm("3,1,5,3,1", 0),
+        ("3".to_owned(), Some(Halt::NoOperand { ip: 4 }))
+    );
+    // jnz 1 lands on its own operand: bxl 5, then jnz 1 again, forever
+    assert_eq!(vm("3,1,5,3,1", 1), ("".to_owned(), None));
+    // jnz 5 runs bxl 0 and out A out of operands
+    assert_eq!(vm("0,3,5,4,3,1,0,5,4", 20), ("2,2".to_owned(), end(9)));
+    // combo 7 is only a problem when an instruction uses it
+    assert_eq!(vm("3,4,0,7", 1), ("".to_owned(), end(4)));
+    let reserved = Halt::Reserved { ip: 2, opcode: 0 };
+    assert_eq!(vm("3,4,0,7", 0), ("".to_owned(), Some(reserved)));
+    // shifting by a large amount clears the register
+    assert_eq!(vm("6,4,5,5", 100), ("0".to_owned(), end(4)));
+
+    // part 1 says how an invalid program stopped instead of answering
+    let input = |a| format!("Register A: {a}\nRegister B: 0\nRegister C: 0\n\nProgram: 3,4,0,7");
+    assert_eq!(part1(&input(1)), Ok("".to_owned()));
+    assert_eq!(part1(&input(0)), Err(reserved.to_string()));
+}
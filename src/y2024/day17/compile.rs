@@ -0,0 +1,296 @@
+//! Programs compiled to closures
+//!
+//! Brute forcing A runs the same handful of instructions over and over, [`eval`] matches on
+//! every [`Instr`] and [`CoOp`] each time and formats digits as they come. [`Compiled`]
+//! does the matching once: every instruction becomes a closure specialised for its operand
+//! that returns the index of the next one, and digits go into a fixed [`Digits`] buffer.
+//! Part 2 builds A backwards with [`Compiled::first`], part 1 and `aoc bench` run whole
+//! programs.
+//!
+//! [`eval`]: super::eval
+
+use std::time::Instant;
+
+use super::{bytes::shr, until::MaxOut, CoOp, Instr, Puter};
+use crate::{runner::Year, trace::Trace};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub(super) struct Regs {
+    pub a: usize,
+    pub b: usize,
+    pub c: usize,
+}
+
+/// Output digits, running stops once `limit` digits are in
+#[derive(Debug, Clone)]
+pub(super) struct Digits {
+    buf: [u8; Digits::CAP],
+    len: usize,
+    limit: usize,
+}
+
+impl Digits {
+    pub const CAP: usize = 32;
+
+    pub fn new() -> Self {
+        Self::with_limit(Self::CAP)
+    }
+
+    pub fn with_limit(limit: usize) -> Self {
+        Self {
+            buf: [0; Self::CAP],
+            len: 0,
+            limit: limit.min(Self::CAP),
+        }
+    }
+
+    /// Output stopped at `limit` digits, the program may have had more to say
+    pub fn full(&self) -> bool {
+        self.len == self.limit
+    }
+
+    /// Returns `false` once the buffer is full
+    fn push(&mut self, digit: usize) -> bool {
+        self.buf[self.len] = (digit % 8) as u8;
+        self.len += 1;
+        self.len < self.limit
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Same format as part 1 answer
+    pub fn join(&self) -> String {
+        super::join(self.as_slice())
+    }
+}
+
+/// Takes registers and output, returns index of the next instruction to run
+type Op = Box<dyn Fn(&mut Regs, &mut Digits) -> usize>;
+
+/// Index past the end, stops the program
+const STOP: usize = usize::MAX;
+
+pub(super) struct Compiled {
+    ops: Vec<Op>,
+    b: usize,
+    c: usize,
+}
+
+impl Compiled {
+    pub fn new(puter: &Puter) -> Self {
+        let ops = puter
+            .code
+            .0
+            .iter()
+            .enumerate()
+            .map(|(ix, &instr)| op(ix, instr))
+            .collect();
+        Self {
+            ops,
+            b: puter.b,
+            c: puter.c,
+        }
+    }
+
+    /// Run from the start with a given A and initial B and C from the program. Stops when the
+    /// program halts or `out` is full, returns `false` if it's still running after `steps`
+    /// instructions.
+    pub fn run(&self, a: usize, out: &mut Digits, steps: usize) -> bool {
+        let mut regs = Regs {
+            a,
+            b: self.b,
+            c: self.c,
+        };
+        let mut ip = 0;
+        for _ in 0..steps {
+            let Some(op) = self.ops.get(ip) else {
+                return true;
+            };
+            ip = op(&mut regs, out);
+        }
+        ip >= self.ops.len()
+    }
+
+    /// First digit the program outputs for a given A within `steps` instructions
+    pub fn first(&self, a: usize, steps: usize) -> Option<u8> {
+        let mut out = Digits::with_limit(1);
+        self.run(a, &mut out, steps);
+        out.as_slice().first().copied()
+    }
+}
+
+// `div!(a, ..)` writes A back into itself
+#[allow(clippy::assign_op_pattern)]
+fn op(ix: usize, instr: Instr) -> Op {
+    let next = ix + 1;
+    // A / 2^x is a plain shift for literals, registers can shift everything out
+    macro_rules! div {
+        ($reg:ident, $op:expr) => {
+            match $op {
+                CoOp::Lit(x) => Box::new(move |r, _| {
+                    r.$reg = r.a >> x;
+                    next
+                }),
+                CoOp::A => Box::new(move |r, _| {
+                    r.$reg = shr(r.a, r.a);
+                    next
+                }),
+                CoOp::B => Box::new(move |r, _| {
+                    r.$reg = shr(r.a, r.b);
+                    next
+                }),
+                CoOp::C => Box::new(move |r, _| {
+                    r.$reg = shr(r.a, r.c);
+                    next
+                }),
+            }
+        };
+    }
+    // combo operand modulo 8, either into B or to the output
+    macro_rules! low {
+        ($op:expr, |$r:ident, $out:ident, $x:ident| $body:expr) => {
+            match $op {
+                CoOp::Lit($x) => Box::new(move |$r, $out| $body),
+                CoOp::A => Box::new(move |$r, $out| {
+                    let $x = $r.a;
+                    $body
+                }),
+                CoOp::B => Box::new(move |$r, $out| {
+                    let $x = $r.b;
+                    $body
+                }),
+                CoOp::C => Box::new(move |$r, $out| {
+                    let $x = $r.c;
+                    $body
+                }),
+            }
+        };
+    }
+    match instr {
+        Instr::Adv(op) => div!(a, op),
+        Instr::Bdv(op) => div!(b, op),
+        Instr::Cdv(op) => div!(c, op),
+        Instr::Bxl(x) => Box::new(move |r, _| {
+            r.b ^= x;
+            next
+        }),
+        Instr::Bst(op) => low!(op, |r, _out, x| {
+            r.b = x % 8;
+            next
+        }),
+        Instr::Jnz(target) => {
+            let target = target / 2;
+            Box::new(move |r, _| if r.a != 0 { target } else { next })
+        }
+        Instr::Bxc(_) => Box::new(move |r, _| {
+            r.b ^= r.c;
+            next
+        }),
+        Instr::Out(op) => low!(op, |_r, out, x| if out.push(x) { next } else { STOP }),
+    }
+}
+
+/// Brute force A over a range with [`eval`](super::eval) and with [`Compiled`], check both
+/// produce the same output and compare how long they took. Every run gets the same number of
+/// steps so programs that never halt are cut off too.
+pub(super) fn bench(year: &Year, args: &[String]) -> Result<(), String> {
+    const MAX_STEPS: usize = 100_000;
+    let (file, count) = match args {
+        [rest @ .., n] if n.parse::<usize>().is_ok() => (rest, n.parse().unwrap()),
+        args => (args, 100_000),
+    };
+    let puter = super::parse(&super::source(year, file)?);
+
+    let start = Instant::now();
+    let slow = (0..count)
+        .map(|a| {
+            let mut p = puter.clone();
+            p.a = a;
+            let mut out = Vec::new();
+            // same cut off as the compiled version
+            let mut until = MaxOut(Digits::CAP);
+            super::eval(
+                &mut p,
+                &mut |d| out.push(d),
+                &mut until,
+                super::Budget::steps(MAX_STEPS),
+                &Trace::default(),
+            );
+            out
+        })
+        .collect::<Vec<_>>();
+    let eval_time = start.elapsed();
+
+    let start = Instant::now();
+    let compiled = Compiled::new(&puter);
+    let fast = (0..count)
+        .map(|a| {
+            let mut out = Digits::new();
+            compiled.run(a, &mut out, MAX_STEPS);
+            out
+        })
+        .collect::<Vec<_>>();
+    let compiled_time = start.elapsed();
+
+    for (a, (slow, fast)) in slow.iter().zip(&fast).enumerate() {
+        if slow != fast.as_slice() {
+            let slow = super::join(slow);
+            return Err(format!(
+                "A={a}: eval gives {slow}, compiled gives {}",
+                fast.join()
+            ));
+        }
+    }
+    println!("A in 0..{count}, same output from both");
+    println!("eval:     {eval_time:?}");
+    println!("compiled: {compiled_time:?}");
+    Ok(())
+}
+
+#[test]
+fn compiled_matches_eval() {
+    let programs = [
+        "0,1,5,4,3,0",
+        "0,3,5,4,3,0",
+        // every combo operand for every instruction that takes one
+        "2,4,1,3,7,5,6,6,4,0,0,3,5,5,5,6,3,0",
+        "2,4,1,7,7,4,0,3,1,7,4,7,5,5,3,0",
+        "6,5,7,6,0,2,5,4,5,6,3,0",
+    ];
+    for code in programs {
+        let puter = super::parse(&format!(
+            "Register A: 0\nRegister B: 5\nRegister C: 9\n\nProgram: {code}"
+        ));
+        let compiled = Compiled::new(&puter);
+        for a in (0..2000).chain([117440, 2024, 1 << 20]) {
+            let mut p = puter.clone();
+            p.a = a;
+            let mut want = Vec::new();
+            super::eval(
+                &mut p,
+                &mut |d| want.push(d),
+                &mut super::Never,
+                super::Budget::NONE,
+                &Trace::default(),
+            );
+
+            let mut out = Digits::new();
+            assert!(compiled.run(a, &mut out, usize::MAX));
+            assert_eq!(out.as_slice(), want, "{code} with A={a}");
+            assert_eq!(
+                compiled.first(a, usize::MAX),
+                out.as_slice().first().copied()
+            );
+        }
+    }
+
+    // never halts and never outputs
+    let spin = Compiled::new(&super::parse(
+        "Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 3,0",
+    ));
+    let mut out = Digits::new();
+    assert!(!spin.run(1, &mut out, 1000));
+    assert_eq!(spin.first(1, 1000), None);
+}
@@ -0,0 +1,332 @@
+//! Step debugger for `Puter` programs
+//!
+//! Every executed instruction records registers before it ran, so the debugger can go back as
+//! well as forward.
+
+use std::{
+    collections::HashMap,
+    io::{BufRead, Write},
+};
+
+use super::{Budget, Puter, Step};
+use crate::runner::Year;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Reg {
+    A,
+    B,
+    C,
+}
+
+impl std::str::FromStr for Reg {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "a" | "A" => Ok(Reg::A),
+            "b" | "B" => Ok(Reg::B),
+            "c" | "C" => Ok(Reg::C),
+            _ => Err(format!("Expected a, b or c, got {s}")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Break {
+    /// Before executing an instruction at this byte address
+    At(usize),
+    /// After an out instruction
+    Out,
+    /// After a register changes
+    Watch(Reg),
+}
+
+/// Why the debugger stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum Stop {
+    /// Ran as many steps as asked for
+    Done,
+    Break(Break),
+    Halted,
+    /// Rewound all the way to the start
+    Start,
+    /// Registers and `ip` after step `start + len` are the same as after step `start`, the
+    /// program runs the same `len` steps forever
+    Looped {
+        start: usize,
+        len: usize,
+    },
+}
+
+/// Steps `continue` runs before giving up, every one of them is kept for rewinding
+const CONTINUE_STEPS: usize = 1_000_000;
+
+#[derive(Debug, Clone, Copy)]
+struct Snapshot {
+    a: usize,
+    b: usize,
+    c: usize,
+    ip: usize,
+    out: usize,
+}
+
+pub(super) struct Debugger {
+    pub puter: Puter,
+    pub out: Vec<usize>,
+    pub breaks: Vec<Break>,
+    history: Vec<Snapshot>,
+}
+
+impl Debugger {
+    pub fn new(puter: Puter) -> Self {
+        Self {
+            puter,
+            out: Vec::new(),
+            breaks: Vec::new(),
+            history: Vec::new(),
+        }
+    }
+
+    /// Number of instructions executed so far
+    pub fn steps(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn reg(&self, reg: Reg) -> usize {
+        match reg {
+            Reg::A => self.puter.a,
+            Reg::B => self.puter.b,
+            Reg::C => self.puter.c,
+        }
+    }
+
+    pub fn set(&mut self, reg: Reg, value: usize) {
+        match reg {
+            Reg::A => self.puter.a = value,
+            Reg::B => self.puter.b = value,
+            Reg::C => self.puter.c = value,
+        }
+    }
+
+    /// Execute a single instruction, reports the first breakpoint it triggered
+    pub fn step(&mut self) -> Stop {
+        let p = &self.puter;
+        let before = Snapshot {
+            a: p.a,
+            b: p.b,
+            c: p.c,
+            ip: p.ip,
+            out: self.out.len(),
+        };
+        let step = self.puter.step();
+        if step == Step::Halted {
+            return Stop::Halted;
+        }
+        self.history.push(before);
+        if let Step::Out(digit) = step {
+            self.out.push(digit);
+        }
+
+        let hit = |b: &Break| match *b {
+            Break::At(addr) => self.puter.ip * 2 == addr,
+            Break::Out => matches!(step, Step::Out(_)),
+            Break::Watch(Reg::A) => self.puter.a != before.a,
+            Break::Watch(Reg::B) => self.puter.b != before.b,
+            Break::Watch(Reg::C) => self.puter.c != before.c,
+        };
+        match self.breaks.iter().find(|b| hit(b)) {
+            Some(b) => Stop::Break(*b),
+            None => Stop::Done,
+        }
+    }
+
+    /// Run up to `budget` instructions, stopping early on breakpoints, when the program halts
+    /// or when it's stuck in a loop and the budget looks for them
+    pub fn run(&mut self, budget: Budget) -> Stop {
+        let mut seen = HashMap::new();
+        for _ in 0..budget.steps {
+            if budget.loops {
+                let p = &self.puter;
+                let step = self.steps();
+                if let Some(start) = seen.insert((p.a, p.b, p.c, p.ip), step) {
+                    return Stop::Looped {
+                        start,
+                        len: step - start,
+                    };
+                }
+            }
+            let stop = self.step();
+            if stop != Stop::Done {
+                return stop;
+            }
+        }
+        Stop::Done
+    }
+
+    /// Undo the last `steps` instructions
+    pub fn rewind(&mut self, steps: usize) -> Stop {
+        for _ in 0..steps {
+            let Some(s) = self.history.pop() else {
+                return Stop::Start;
+            };
+            (self.puter.a, self.puter.b, self.puter.c) = (s.a, s.b, s.c);
+            self.puter.ip = s.ip;
+            self.out.truncate(s.out);
+        }
+        Stop::Done
+    }
+
+    /// Output so far, in the same format as part 1 answer
+    pub fn output(&self) -> String {
+        let out = self.out.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+        out.join(",")
+    }
+}
+
+const HELP: &str = "\
+s, step [N]        execute N instructions, 1 by default
+c, continue        run until a breakpoint, until the program halts or loops
+b, break ADDR|out  stop before an instruction at a byte address or after out
+w, watch a|b|c     stop after a register changes
+d, delete          remove all breakpoints and watches
+r, rewind [N]      undo N instructions, 1 by default
+o, out             output produced so far
+set a|b|c VALUE    change a register
+l, list            program listing
+q, quit";
+
+/// Interactive debugger over a program from a file or the real input, commands come from stdin
+pub(super) fn debug(year: &Year, args: &[String]) -> Result<(), String> {
+    let mut dbg = Debugger::new(super::parse(&super::source(year, args)?));
+    println!("{HELP}");
+    status(&dbg, Stop::Done);
+
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        print!("> ");
+        std::io::stdout().flush().map_err(|e| e.to_string())?;
+        let Some(line) = lines.next() else {
+            return Ok(());
+        };
+        let line = line.map_err(|e| e.to_string())?;
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let count = |n: Option<&&str>| match n {
+            Some(n) => n.parse().map_err(|_| format!("Bad count: {n}")),
+            None => Ok(1),
+        };
+        let res = match words[..] {
+            [] => Ok(Some(dbg.run(Budget::NONE.limit(1)))),
+            ["s" | "step", ref n @ ..] => {
+                count(n.first()).map(|n| Some(dbg.run(Budget::NONE.limit(n))))
+            }
+            ["c" | "continue"] => match dbg.run(Budget::steps(CONTINUE_STEPS)) {
+                Stop::Done => Err(format!("still running after {CONTINUE_STEPS} steps")),
+                stop => Ok(Some(stop)),
+            },
+            ["b" | "break", "out"] => {
+                dbg.breaks.push(Break::Out);
+                Ok(None)
+            }
+            ["b" | "break", addr] => addr
+                .parse()
+                .map(|addr| dbg.breaks.push(Break::At(addr)))
+                .map(|_| None)
+                .map_err(|_| format!("Bad address: {addr}")),
+            ["w" | "watch", reg] => reg.parse().map(|r| {
+                dbg.breaks.push(Break::Watch(r));
+                None
+            }),
+            ["d" | "delete"] => {
+                dbg.breaks.clear();
+                Ok(None)
+            }
+            ["r" | "rewind", ref n @ ..] => count(n.first()).map(|n| Some(dbg.rewind(n))),
+            ["o" | "out"] => {
+                println!("{}", dbg.output());
+                Ok(None)
+            }
+            ["set", reg, value] => reg.parse().and_then(|r| {
+                let value = value.parse().map_err(|_| format!("Bad value: {value}"))?;
+                dbg.set(r, value);
+                Ok(Some(Stop::Done))
+            }),
+            ["l" | "list"] => {
+                print!("{}", dbg.puter.code);
+                Ok(None)
+            }
+            ["q" | "quit"] => return Ok(()),
+            _ => Err(HELP.to_owned()),
+        };
+        match res {
+            Ok(Some(stop)) => status(&dbg, stop),
+            Ok(None) => {}
+            Err(err) => println!("{err}"),
+        }
+    }
+}
+
+fn status(dbg: &Debugger, stop: Stop) {
+    match stop {
+        Stop::Done => {}
+        Stop::Break(Break::Watch(r)) => println!("{r:?} changed to {}", dbg.reg(r)),
+        Stop::Break(b) => println!("stopped at {b:?}"),
+        Stop::Halted => println!("halted, output: {}", dbg.output()),
+        Stop::Start => println!("at the start"),
+        Stop::Looped { start, len } => {
+            println!("stuck in a {len} step loop from step {start}")
+        }
+    }
+    let p = &dbg.puter;
+    println!(
+        "step {}: a={} b={} c={} out=[{}]",
+        dbg.steps(),
+        p.a,
+        p.b,
+        p.c,
+        dbg.output()
+    );
+    if p.ip < p.code.0.len() {
+        println!("{}", p.code.line(p.ip));
+    }
+}
+
+#[test]
+fn debugger_works() {
+    let puter =
+        super::parse("Register A: 729\nRegister B: 0\nRegister C: 0\n\nProgram: 0,1,5,4,3,0");
+    let mut dbg = Debugger::new(puter);
+
+    dbg.breaks.push(Break::Out);
+    assert_eq!(dbg.run(Budget::NONE), Stop::Break(Break::Out));
+    assert_eq!((dbg.steps(), dbg.output().as_str()), (2, "4"));
+
+    dbg.breaks = vec![Break::At(4)];
+    assert_eq!(dbg.run(Budget::NONE), Stop::Break(Break::At(4)));
+    assert_eq!(dbg.steps(), 5);
+
+    dbg.breaks = vec![Break::Watch(Reg::A)];
+    assert_eq!(dbg.run(Budget::NONE), Stop::Break(Break::Watch(Reg::A)));
+    assert_eq!(dbg.reg(Reg::A), 729 / 8);
+
+    assert_eq!(dbg.rewind(4), Stop::Done);
+    assert_eq!(
+        (dbg.steps(), dbg.output().as_str(), dbg.reg(Reg::A)),
+        (3, "4", 364)
+    );
+    assert_eq!(dbg.rewind(10), Stop::Start);
+    assert_eq!(
+        (dbg.puter.ip, dbg.output().as_str(), dbg.reg(Reg::A)),
+        (0, "", 729)
+    );
+
+    dbg.breaks.clear();
+    assert_eq!(dbg.run(Budget::NONE), Stop::Halted);
+    assert_eq!(dbg.output(), "4,6,3,5,6,3,5,2,1,0");
+
+    // jumps to itself forever without output
+    let puter = super::parse("Register A: 1\nRegister B: 0\nRegister C: 0\n\nProgram: 3,0");
+    let mut dbg = Debugger::new(puter);
+    assert_eq!(dbg.run(Budget::NONE.limit(3)), Stop::Done);
+    let looped = Stop::Looped { start: 3, len: 1 };
+    assert_eq!(dbg.run(Budget::steps(CONTINUE_STEPS)), looped);
+}
@@ -0,0 +1,850 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
+mod analysis;
+mod bytes;
+mod compile;
+mod debugger;
+mod symbolic;
+mod until;
+
+use crate::{
+    runner::{Solution, Tool, Year},
+    trace::{Event, Level, Trace},
+};
+use analysis::Analysis;
+use until::{Never, Prefix, Until};
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(17, 1, |i, p| part1(&parse(i), &p.trace).to_string()),
+    Solution::new(17, 1, |i, _| {
+        bytes::part1(i).unwrap_or_else(|err| panic!("{err}"))
+    })
+    .named("bytes"),
+    Solution::new(17, 1, |i, p| part1c(&parse(i), &p.trace)).named("compiled"),
+    Solution::new(17, 2, |i, p| part2(&parse(i), &p.trace).to_string()).named("backwards"),
+    Solution::new(17, 2, |i, _| part2s(&parse(i)).to_string()).named("symbolic"),
+];
+
+pub(crate) const TOOLS: &[Tool] = &[
+    Tool {
+        name: "disasm",
+        usage: "[FILE]  Annotated listing of a day 17 program, real input by default",
+        run: disasm,
+    },
+    Tool {
+        name: "asm",
+        usage: "[FILE]     Assemble mnemonic source from a file or stdin into a day 17 program",
+        run: asm,
+    },
+    Tool {
+        name: "debug",
+        usage: "[FILE]   Step through a day 17 program, real input by default",
+        run: debugger::debug,
+    },
+    Tool {
+        name: "symbolic",
+        usage: "[FILE] [OUTPUT]  Paths through a day 17 program with A unknown, \
+                or smallest A that makes it output OUTPUT",
+        run: symbolic::symbolic,
+    },
+    Tool {
+        name: "exec",
+        usage: "[FILE]    Run a day 17 program byte by byte and report how it stops",
+        run: bytes::exec,
+    },
+    Tool {
+        name: "bench",
+        usage: "[FILE] [N]  Brute force A in 0..N with eval and compiled closures, compare",
+        run: compile::bench,
+    },
+    Tool {
+        name: "analyze",
+        usage: "[FILE] Control flow, loops and register dataflow of a day 17 program",
+        run: analysis::analyze,
+    },
+];
+
+#[derive(Debug, Clone)]
+struct Puter {
+    a: usize,
+    b: usize,
+    c: usize,
+    ip: usize,
+    code: Program,
+    raw: String,
+}
+
+/// Decoded program, displays as an annotated listing:
+///
+/// ```text
+/// l0:
+///   0  bst A      ; b = a % 8
+///   2  bxl 7      ; b = b ^ 7
+///  ..
+///  14  jnz l0     ; if a != 0 goto 0
+/// ```
+///
+/// Each line starts with a byte address, jump targets get labels.
+#[derive(Debug, Clone)]
+struct Program(Vec<Instr>);
+
+#[derive(Debug, Clone, Copy)]
+enum Instr {
+    /// The adv instruction (opcode 0) performs division. The numerator is the value in the A
+    /// register. The denominator is found by raising 2 to the power of the instruction's combo
+    /// operand. (So, an operand of 2 would divide A by 4 (2^2); an operand of 5 would divide A by
+    /// 2^B.) The result of the division operation is truncated to an integer and then written to
+    /// the A register.
+    ///
+    Adv(CoOp),
+
+    /// The bxl instruction (opcode 1) calculates the bitwise XOR of register B and the
+    /// instruction's literal operand, then stores the result in register B.
+    Bxl(usize),
+
+    /// The bst instruction (opcode 2) calculates the value of its combo operand modulo 8 (thereby
+    /// keeping only its lowest 3 bits), then writes that value to the B register.
+    Bst(CoOp),
+
+    /// The jnz instruction (opcode 3) does nothing if the A register is 0. However, if the A
+    /// register is not zero, it jumps by setting the instruction pointer to the value of its
+    /// literal operand; if this instruction jumps, the instruction pointer is not increased by 2
+    /// after this instruction.
+    Jnz(usize),
+
+    /// The bxc instruction (opcode 4) calculates the bitwise XOR of register B and register C, then
+    /// stores the result in register B. (For legacy reasons, this instruction reads an operand but
+    /// ignores it.)
+    Bxc(usize),
+
+    /// The out instruction (opcode 5) calculates the value of its combo operand modulo 8, then
+    /// outputs that value. (If a program outputs multiple values, they are separated by commas.)
+    Out(CoOp),
+
+    /// The bdv instruction (opcode 6) works exactly like the adv instruction except that the result
+    /// is stored in the B register. (The numerator is still read from the A register.)
+    Bdv(CoOp),
+
+    /// The cdv instruction (opcode 7) works exactly like the adv instruction except that the result
+    /// is stored in the C register. (The numerator is still read from the A register.)
+    Cdv(CoOp),
+}
+/// Combo operand
+#[derive(Debug, Clone, Copy)]
+enum CoOp {
+    Lit(usize),
+    A,
+    B,
+    C,
+}
+
+impl std::fmt::Display for CoOp {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CoOp::Lit(l) => write!(f, "{l}"),
+            CoOp::A => write!(f, "A"),
+            CoOp::B => write!(f, "B"),
+            CoOp::C => write!(f, "C"),
+        }
+    }
+}
+
+impl CoOp {
+    fn new(op: usize) -> Result<Self, String> {
+        Ok(match op {
+            0..=3 => CoOp::Lit(op),
+            4 => CoOp::A,
+            5 => CoOp::B,
+            6 => CoOp::C,
+            _ => return Err(format!("combo operand {op} is reserved")),
+        })
+    }
+
+    fn encode(self) -> usize {
+        match self {
+            CoOp::Lit(l) => l,
+            CoOp::A => 4,
+            CoOp::B => 5,
+            CoOp::C => 6,
+        }
+    }
+
+    /// Value as it would appear in an expression: `3` or `a`
+    fn meaning(self) -> String {
+        self.to_string().to_lowercase()
+    }
+}
+
+impl Instr {
+    fn decode(opcode: usize, op: usize) -> Result<Self, String> {
+        use Instr as I;
+        Ok(match opcode {
+            0 => I::Adv(CoOp::new(op)?),
+            1 => I::Bxl(op),
+            2 => I::Bst(CoOp::new(op)?),
+            3 => I::Jnz(op),
+            4 => I::Bxc(op),
+            5 => I::Out(CoOp::new(op)?),
+            6 => I::Bdv(CoOp::new(op)?),
+            7 => I::Cdv(CoOp::new(op)?),
+            _ => return Err(format!("unknown opcode {opcode}")),
+        })
+    }
+
+    /// Opcode and raw operand
+    fn encode(&self) -> (usize, usize) {
+        match *self {
+            Instr::Adv(c) => (0, c.encode()),
+            Instr::Bxl(l) => (1, l),
+            Instr::Bst(c) => (2, c.encode()),
+            Instr::Jnz(t) => (3, t),
+            Instr::Bxc(l) => (4, l),
+            Instr::Out(c) => (5, c.encode()),
+            Instr::Bdv(c) => (6, c.encode()),
+            Instr::Cdv(c) => (7, c.encode()),
+        }
+    }
+
+    fn mnemonic(&self) -> &'static str {
+        match self {
+            Instr::Adv(_) => "adv",
+            Instr::Bxl(_) => "bxl",
+            Instr::Bst(_) => "bst",
+            Instr::Jnz(_) => "jnz",
+            Instr::Bxc(_) => "bxc",
+            Instr::Out(_) => "out",
+            Instr::Bdv(_) => "bdv",
+            Instr::Cdv(_) => "cdv",
+        }
+    }
+}
+
+impl Program {
+    /// Byte address a jump can land on and start executing from
+    fn label(&self, target: usize) -> Option<String> {
+        (target.is_multiple_of(2) && target / 2 < self.0.len()).then(|| format!("l{target}"))
+    }
+
+    /// Listing line for the instruction at `ix`: address, mnemonic, operand and what it does
+    fn line(&self, ix: usize) -> String {
+        let instr = self.0[ix];
+        let (operand, meaning) = match instr {
+            Instr::Adv(c) => (c.to_string(), format!("a = a >> {}", c.meaning())),
+            Instr::Bxl(l) => (l.to_string(), format!("b = b ^ {l}")),
+            Instr::Bst(c) => (c.to_string(), format!("b = {} % 8", c.meaning())),
+            Instr::Jnz(t) => match self.label(t) {
+                Some(label) => (label, format!("if a != 0 goto {t}")),
+                None if t % 2 == 1 => (t.to_string(), "jumps into an operand".to_owned()),
+                None => (t.to_string(), "halts if a != 0".to_owned()),
+            },
+            Instr::Bxc(l) => (l.to_string(), "b = b ^ c".to_owned()),
+            Instr::Out(c) => (c.to_string(), format!("out {} % 8", c.meaning())),
+            Instr::Bdv(c) => (c.to_string(), format!("b = a >> {}", c.meaning())),
+            Instr::Cdv(c) => (c.to_string(), format!("c = a >> {}", c.meaning())),
+        };
+        let code = format!("{} {operand}", instr.mnemonic());
+        format!("{:>3}  {code:<10} ; {meaning}", ix * 2)
+    }
+
+    /// Comma separated opcodes and operands, as in the `Program:` line
+    fn encode(&self) -> String {
+        join(&self.bytes())
+    }
+
+    /// Program as opcodes and operands
+    fn bytes(&self) -> Vec<u8> {
+        self.0
+            .iter()
+            .flat_map(|i| {
+                let (opcode, operand) = i.encode();
+                [opcode as u8, operand as u8]
+            })
+            .collect()
+    }
+
+    /// Parse mnemonic source, one instruction per line:
+    ///
+    /// ```text
+    /// ; prints digits of A, lowest first
+    /// loop: out A   ; label can share a line with an instruction
+    ///       adv 3
+    ///       jnz loop
+    /// ```
+    ///
+    /// Combo operands are `0`..`3` or `A`, `B`, `C`, jump targets are labels or byte addresses,
+    /// `bxc` operand is optional. A number in front of an instruction is its expected byte
+    /// address, so listings produced by `Display` assemble back into the same program.
+    fn assemble(source: &str) -> Result<Self, String> {
+        let mut labels = Vec::new();
+        let mut lines = Vec::new();
+        for (lineno, line) in source.lines().enumerate() {
+            let err = |e: String| format!("line {}: {e}", lineno + 1);
+            let mut line = line.split(';').next().unwrap_or_default().trim();
+            while let Some((label, rest)) = line.split_once(':') {
+                let label = label.trim();
+                if !label.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+                    return Err(err(format!("bad label {label:?}")));
+                }
+                if labels.iter().any(|(l, _)| l == label) {
+                    return Err(err(format!("duplicate label {label}")));
+                }
+                labels.push((label.to_owned(), lines.len() * 2));
+                line = rest.trim();
+            }
+            let mut words = line.split_whitespace().collect::<Vec<_>>();
+            if let Some(addr) = words.first().and_then(|w| w.parse::<usize>().ok()) {
+                if addr != lines.len() * 2 {
+                    return Err(err(format!(
+                        "expected address {}, got {addr}",
+                        lines.len() * 2
+                    )));
+                }
+                words.remove(0);
+            }
+            match words[..] {
+                [] => {}
+                [mnemonic] => lines.push((lineno, mnemonic, None)),
+                [mnemonic, operand] => lines.push((lineno, mnemonic, Some(operand))),
+                _ => return Err(err(format!("expected an instruction, got {line:?}"))),
+            }
+        }
+
+        let code = lines
+            .into_iter()
+            .map(|(lineno, mnemonic, operand)| {
+                let err = |e: String| format!("line {}: {e}", lineno + 1);
+                let literal = |op: &str| match op.parse::<usize>() {
+                    Ok(l) if l < 8 => Ok(l),
+                    _ => Err(err(format!(
+                        "expected a number between 0 and 7, got {op:?}"
+                    ))),
+                };
+                let combo = |op: &str| match op {
+                    "A" | "a" => Ok(CoOp::A),
+                    "B" | "b" => Ok(CoOp::B),
+                    "C" | "c" => Ok(CoOp::C),
+                    _ => CoOp::new(literal(op)?).map_err(err),
+                };
+                let target = |op: &str| match labels.iter().find(|(l, _)| l == op) {
+                    Some((_, addr)) if *addr < 8 => Ok(*addr),
+                    Some((_, addr)) => Err(err(format!("{op} at {addr} is too far to jump to"))),
+                    None if op.starts_with(|c: char| c.is_ascii_digit()) => literal(op),
+                    None => Err(err(format!("unknown label {op}"))),
+                };
+                Ok(match (mnemonic, operand) {
+                    ("adv", Some(op)) => Instr::Adv(combo(op)?),
+                    ("bxl", Some(op)) => Instr::Bxl(literal(op)?),
+                    ("bst", Some(op)) => Instr::Bst(combo(op)?),
+                    ("jnz", Some(op)) => Instr::Jnz(target(op)?),
+                    ("bxc", None) => Instr::Bxc(0),
+                    ("bxc", Some(op)) => Instr::Bxc(literal(op)?),
+                    ("out", Some(op)) => Instr::Out(combo(op)?),
+                    ("bdv", Some(op)) => Instr::Bdv(combo(op)?),
+                    ("cdv", Some(op)) => Instr::Cdv(combo(op)?),
+                    (_, None) => return Err(err(format!("{mnemonic} needs an operand"))),
+                    _ => return Err(err(format!("unknown instruction {mnemonic}"))),
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Program(code).checked()
+    }
+
+    /// Jumps to an odd address inside the program land on an operand, which only
+    /// [`bytes::Vm`] runs correctly. Past the end both halt the same way.
+    fn checked(self) -> Result<Self, String> {
+        let len = self.0.len() * 2;
+        let odd = self.0.iter().find_map(|i| match *i {
+            Instr::Jnz(t) if t % 2 == 1 && t < len => Some(t),
+            _ => None,
+        });
+        match odd {
+            Some(t) => Err(format!(
+                "jump to odd address {t} lands on an operand, `exec` runs it byte by byte"
+            )),
+            None => Ok(self),
+        }
+    }
+}
+
+impl FromStr for Program {
+    type Err = String;
+
+    /// Comma separated opcodes and operands, as in the `Program:` line
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let bytes = s
+            .trim()
+            .split(',')
+            .map(|b| match b.trim().parse::<usize>() {
+                Ok(b) if b < 8 => Ok(b),
+                _ => Err(format!("expected a number between 0 and 7, got {b:?}")),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if bytes.len() % 2 != 0 {
+            return Err("program must have an even number of bytes".to_owned());
+        }
+        let code = bytes
+            .chunks(2)
+            .map(|i| Instr::decode(i[0], i[1]))
+            .collect::<Result<_, _>>()?;
+        Program(code).checked()
+    }
+}
+
+impl fmt::Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let targets = self
+            .0
+            .iter()
+            .filter_map(|i| match i {
+                Instr::Jnz(t) => Some(*t),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        for ix in 0..self.0.len() {
+            if targets.contains(&(ix * 2)) {
+                writeln!(f, "l{}:", ix * 2)?;
+            }
+            writeln!(f, "{}", self.line(ix))?;
+        }
+        Ok(())
+    }
+}
+
+impl Puter {
+    fn resolve(&self, op: &CoOp) -> usize {
+        match op {
+            CoOp::Lit(l) => *l,
+            CoOp::A => self.a,
+            CoOp::B => self.b,
+            CoOp::C => self.c,
+        }
+    }
+}
+
+/// What a single instruction did
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+enum Step {
+    Ran,
+    Out(usize),
+    Halted,
+}
+
+impl Puter {
+    /// Execute instruction at `ip`
+    fn step(&mut self) -> Step {
+        let Some(instr) = self.code.0.get(self.ip).copied() else {
+            return Step::Halted;
+        };
+        self.ip += 1;
+        match instr {
+            Instr::Adv(op) => {
+                self.a = bytes::shr(self.a, self.resolve(&op));
+            }
+            Instr::Bxl(op) => {
+                self.b ^= op;
+            }
+            Instr::Bst(op) => {
+                self.b = self.resolve(&op) % 8;
+            }
+            Instr::Jnz(op) => {
+                if self.a != 0 {
+                    // operand is a byte address, odd ones are rejected by `Program::checked`
+                    self.ip = op / 2;
+                }
+            }
+            Instr::Bxc(_) => {
+                self.b ^= self.c;
+            }
+            Instr::Out(op) => {
+                return Step::Out(self.resolve(&op) % 8);
+            }
+            Instr::Bdv(op) => {
+                self.b = bytes::shr(self.a, self.resolve(&op));
+            }
+            Instr::Cdv(op) => {
+                self.c = bytes::shr(self.a, self.resolve(&op));
+            }
+        }
+        Step::Ran
+    }
+}
+
+/// How long [`eval`] may run before giving up
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Budget {
+    steps: usize,
+    /// Remember every state to notice when one repeats, costs a hash map insert per step
+    loops: bool,
+}
+
+impl Budget {
+    /// For programs that are known to halt
+    const NONE: Budget = Budget {
+        steps: usize::MAX,
+        loops: false,
+    };
+
+    /// At most `steps` instructions, stopping early if the program is stuck in a loop
+    fn steps(steps: usize) -> Self {
+        Self { steps, loops: true }
+    }
+
+    /// Same loop detection, at most `steps` instructions
+    fn limit(self, steps: usize) -> Self {
+        Self { steps, ..self }
+    }
+}
+
+/// Why [`eval`] returned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    /// Ran past the end of the program
+    Halted,
+    /// `until` said to stop
+    Stopped,
+    /// Ran out of steps
+    Exhausted,
+    /// Registers and `ip` after step `start + len` are the same as after step `start`, so the
+    /// program will repeat the same `len` steps forever
+    Looped { start: usize, len: usize },
+}
+
+/// Run until the program halts, `until` says to stop or `budget` runs out. Digits go to `out`
+/// as they come out.
+fn eval<U: Until + ?Sized>(
+    puter: &mut Puter,
+    out: &mut impl FnMut(u8),
+    until: &mut U,
+    budget: Budget,
+    trace: &Trace,
+) -> Outcome {
+    let mut seen = HashMap::new();
+    for step in 0..budget.steps {
+        // instructions are two bytes, the puzzle counts `ip` in bytes
+        trace.emit(Level::Trace, || Event::Registers {
+            ip: puter.ip * 2,
+            a: puter.a,
+            b: puter.b,
+            c: puter.c,
+        });
+        if budget.loops {
+            if let Some(start) = seen.insert((puter.a, puter.b, puter.c, puter.ip), step) {
+                return Outcome::Looped {
+                    start,
+                    len: step - start,
+                };
+            }
+        }
+        let digit = match puter.step() {
+            Step::Ran => None,
+            Step::Out(digit) => Some(digit as u8),
+            Step::Halted => return Outcome::Halted,
+        };
+        if let Some(digit) = digit {
+            out(digit);
+        }
+        if until.stop(puter, digit) {
+            return Outcome::Stopped;
+        }
+    }
+    Outcome::Exhausted
+}
+
+/// Digits in the same format as part 1 answer
+fn join(digits: &[u8]) -> String {
+    let digits = digits.iter().map(|d| d.to_string()).collect::<Vec<_>>();
+    digits.join(",")
+}
+
+fn parse(input: &str) -> Puter {
+    let mut input = input.lines();
+    let a = input.next().unwrap()[12..].parse().unwrap();
+    let b = input.next().unwrap()[12..].parse().unwrap();
+    let c = input.next().unwrap()[12..].parse().unwrap();
+    input.next();
+    let raw = input.next().unwrap()[9..].to_owned();
+    let code = raw.parse().unwrap();
+
+    Puter {
+        a,
+        b,
+        c,
+        ip: 0,
+        code,
+        raw,
+    }
+}
+
+/// Programs that are still running after this many steps are reported as stuck
+const MAX_STEPS: usize = 10_000_000;
+
+fn part1(input: &Puter, trace: &Trace) -> String {
+    let mut out = Vec::new();
+    let mut puter = input.clone();
+    match eval(
+        &mut puter,
+        &mut |d| out.push(d),
+        &mut Never,
+        Budget::steps(MAX_STEPS),
+        trace,
+    ) {
+        Outcome::Halted => join(&out),
+        Outcome::Exhausted => panic!("still running after {MAX_STEPS} steps"),
+        Outcome::Looped { start, len } => panic!("stuck in a {len} step loop from step {start}"),
+        Outcome::Stopped => unreachable!(),
+    }
+}
+
+/// Same as [`part1`], with the program compiled to closures. Output that doesn't fit in
+/// [`compile::Digits`] and programs that don't halt go to [`part1`] instead.
+fn part1c(input: &Puter, trace: &Trace) -> String {
+    let mut out = compile::Digits::new();
+    let halted = compile::Compiled::new(input).run(input.a, &mut out, MAX_STEPS);
+    if !halted || out.full() {
+        return part1(input, trace);
+    }
+    out.join()
+}
+
+/// Builds A backwards a digit at a time when the analysis says that works, solves formulas
+/// with [`part2s`] otherwise
+fn part2(input: &Puter, trace: &Trace) -> usize {
+    let analysis = Analysis::new(&input.code);
+    trace.emit(Level::Info, || {
+        Event::Note(format!("\n{}{analysis}", input.code))
+    });
+    match analysis.backwards(&input.code) {
+        Ok(()) => quine(input, trace).unwrap_or_else(|err| panic!("{err}")),
+        Err(err) => {
+            trace.emit(Level::Info, || {
+                Event::Note(format!("backwards search doesn't apply, {err}"))
+            });
+            part2s(input)
+        }
+    }
+}
+
+/// Same as [`part2`], but by solving formulas for the output digits, works for any program as
+/// long as A fits in 3 bits per output digit
+fn part2s(input: &Puter) -> usize {
+    symbolic::quine(input).expect("no value of A makes the program output itself")
+}
+
+/// Check that building A backwards from the last digit works for this program, see
+/// [`Analysis::backwards`]
+fn quine_shape(code: &Program) -> Result<(), String> {
+    Analysis::new(code).backwards(code)
+}
+
+/// Smallest A that makes the program output itself
+fn quine(input: &Puter, trace: &Trace) -> Result<usize, String> {
+    quine_shape(&input.code)?;
+    let compiled = compile::Compiled::new(input);
+    extend(input, &compiled, &input.code.bytes(), 0, trace)
+        .ok_or_else(|| "no value of A makes the program output itself".to_owned())
+}
+
+/// `a` makes the program output the tail of `digits` after the rest, try to extend it with 3
+/// more bits for each of the remaining digits. Smaller bits go first so the first complete
+/// answer is the smallest one.
+fn extend(
+    input: &Puter,
+    compiled: &compile::Compiled,
+    digits: &[u8],
+    a: usize,
+    trace: &Trace,
+) -> Option<usize> {
+    let Some((&digit, rest)) = digits.split_last() else {
+        return quine_check(input, a).then_some(a);
+    };
+    (0..8)
+        .map(|bits| a << 3 | bits)
+        .filter(|&a| a != 0 && compiled.first(a, MAX_STEPS) == Some(digit))
+        .find_map(|a| {
+            trace.emit(Level::Debug, || Event::Expand {
+                state: &a,
+                cost: rest.len(),
+            });
+            extend(input, compiled, rest, a, trace)
+        })
+}
+
+/// Program outputs exactly itself with this value of A
+fn quine_check(input: &Puter, a: usize) -> bool {
+    let want = input.code.bytes();
+    let mut puter = input.clone();
+    puter.a = a;
+    let mut until = Prefix::new(&want);
+    let outcome = eval(
+        &mut puter,
+        &mut |_| {},
+        &mut until,
+        Budget::NONE,
+        &Trace::default(),
+    );
+    outcome == Outcome::Halted && until.matched() == want.len()
+}
+
+/// Puzzle input from a file given to a tool, real input if there's none
+fn source(year: &Year, args: &[String]) -> Result<String, String> {
+    match args {
+        [] => year.tool_input(17, None).map(|i| i.0),
+        [path] => year.tool_input(17, Some(path)).map(|i| i.0),
+        _ => Err("Expected at most one file".to_owned()),
+    }
+}
+
+/// Program from the puzzle input or from a file with just the comma separated program
+fn disasm(year: &Year, args: &[String]) -> Result<(), String> {
+    let source = source(year, args)?;
+    let program = source
+        .lines()
+        .find_map(|l| l.strip_prefix("Program:"))
+        .unwrap_or(&source);
+    print!("{}", program.parse::<Program>()?);
+    Ok(())
+}
+
+/// Mnemonic source from a file or stdin
+fn asm(_year: &Year, args: &[String]) -> Result<(), String> {
+    let source = match args {
+        [] => std::io::read_to_string(std::io::stdin()).map_err(|e| e.to_string())?,
+        [path] => std::fs::read_to_string(path).map_err(|e| format!("Can't read {path}: {e}"))?,
+        _ => return Err("Expected at most one file".to_owned()),
+    };
+    println!("{}", Program::assemble(&source)?.encode());
+    Ok(())
+}
+
+#[test]
+fn listing_works() {
+    let program = "2,4,1,7,7,5,0,3,4,0,1,7,5,5,3,0"
+        .parse::<Program>()
+        .unwrap();
+    assert_eq!(
+        program.to_string(),
+        "\
+l0:
+  0  bst A      ; b = a % 8
+  2  bxl 7      ; b = b ^ 7
+  4  cdv B      ; c = a >> b
+  6  adv 3      ; a = a >> 3
+  8  bxc 0      ; b = b ^ c
+ 10  bxl 7      ; b = b ^ 7
+ 12  out B      ; out b % 8
+ 14  jnz l0     ; if a != 0 goto 0
+"
+    );
+    assert!("0,7".parse::<Program>().is_err());
+    assert!("0,1,2".parse::<Program>().is_err());
+    // lands on the operand of `jnz`
+    assert!("0,1,3,3".parse::<Program>().is_err());
+    assert!(Program::assemble("adv 1\njnz 3").is_err());
+}
+
+#[test]
+fn assembler_round_trips() {
+    for raw in [
+        "2,4,1,7,7,5,0,3,4,0,1,7,5,5,3,0",
+        "0,3,5,4,3,0",
+        "4,5,3,2,0,1,3,6,3,4",
+        // past the end, halts either way
+        "0,1,3,7,5,4",
+    ] {
+        let program = raw.parse::<Program>().unwrap();
+        let source = program.to_string();
+        assert_eq!(
+            Program::assemble(&source).unwrap().encode(),
+            raw,
+            "{source}"
+        );
+    }
+}
+
+#[test]
+fn assembler_works() {
+    let source = "\
+; digits of A, lowest first
+loop: out a
+      adv 3   ; next digit
+      jnz loop
+";
+    let code = Program::assemble(source).unwrap();
+    assert_eq!(code.encode(), "5,4,0,3,3,0");
+
+    let mut puter = parse(&format!(
+        "Register A: 83\nRegister B: 0\nRegister C: 0\n\nProgram: {}",
+        code.encode()
+    ));
+    let mut out = Vec::new();
+    eval(
+        &mut puter,
+        &mut |d| out.push(d),
+        &mut Never,
+        Budget::NONE,
+        &Trace::default(),
+    );
+    assert_eq!(out, [3, 2, 1]);
+
+    assert!(Program::assemble("jnz nowhere").is_err());
+    assert!(Program::assemble("adv 7").is_err());
+    assert!(Program::assemble("bxl 8").is_err());
+    assert!(Program::assemble("2 adv 1").is_err());
+    assert!(Program::assemble("x: adv 1\nx: adv 1").is_err());
+}
+
+#[test]
+fn quine_shape_works() {
+    let shape = |src: &str| quine_shape(&Program::assemble(src).unwrap());
+    assert!(shape("bst A\nbxl 7\ncdv B\nadv 3\nbxc\nout B\njnz 0").is_ok());
+    assert!(shape("adv 3\nout A\njnz 0").is_ok());
+    assert!(shape("adv 3\nout A").is_err());
+    assert!(shape("adv 2\nout A\njnz 0").is_err());
+    assert!(shape("adv 3\nout A\nout A\njnz 0").is_err());
+    assert!(shape("adv 3\nbxl 1\nout B\njnz 0").is_err());
+}
+
+#[test]
+fn budget_works() {
+    let run = |code: &str, a: usize, budget: Budget| {
+        let mut puter = parse(&format!(
+            "Register A: {a}\nRegister B: 0\nRegister C: 0\n\nProgram: {code}"
+        ));
+        let mut out = Vec::new();
+        let outcome = eval(
+            &mut puter,
+            &mut |d| out.push(d),
+            &mut Never,
+            budget,
+            &Trace::default(),
+        );
+        (outcome, join(&out))
+    };
+    let looped = |start, len| Outcome::Looped { start, len };
+
+    let halted = (Outcome::Halted, "4,6,3,5,6,3,5,2,1,0".to_owned());
+    assert_eq!(run("0,1,5,4,3,0", 729, Budget::steps(1000)), halted);
+    assert_eq!(run("3,0", 1, Budget::steps(1000)).0, looped(0, 1));
+    // B flips back and forth, state repeats every other pass
+    assert_eq!(
+        run("1,1,5,5,3,0", 1, Budget::steps(1000)),
+        (looped(0, 6), "1,0".to_owned())
+    );
+    // jumps to itself once A stops changing
+    assert_eq!(run("0,1,3,4,3,4", 4, Budget::steps(1000)).0, looped(2, 1));
+    let blind = Budget {
+        steps: 5,
+        loops: false,
+    };
+    assert_eq!(run("3,0", 1, blind).0, Outcome::Exhausted);
+}
+
+#[test]
+fn long_output_works() {
+    // one digit per bit of A, more than fit in the compiled buffer
+    let puter =
+        parse("Register A: 1099511627776\nRegister B: 0\nRegister C: 0\n\nProgram: 5,4,0,1,3,0");
+    let want = part1(&puter, &Trace::default());
+    assert_eq!(want.split(',').count(), 41);
+    assert_eq!(part1c(&puter, &Trace::default()), want);
+}
@@ -0,0 +1,528 @@
+//! Symbolic execution of `Puter` programs
+//!
+//! Register A holds unknown bits, everything computed from it becomes a boolean formula over
+//! those bits. A jump that depends on A splits execution in two, so a run gives a list of paths,
+//! each with a condition on A and the output digits as formulas. Questions like "which A makes
+//! the program output X" are then answered by a small solver that assigns bits of A from the
+//! top down and backtracks as soon as a formula can't be satisfied anymore.
+
+use std::collections::HashMap;
+
+use super::{CoOp, Instr, Puter};
+use crate::runner::Year;
+
+/// Index of a formula in [`Formulas`]
+pub(super) type Id = usize;
+
+pub(super) const F: Id = 0;
+pub(super) const T: Id = 1;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+enum Node {
+    Const(bool),
+    /// Bit of A
+    Var(usize),
+    Not(Id),
+    And(Id, Id),
+    Xor(Id, Id),
+}
+
+/// Shared storage for formulas, identical formulas get the same [`Id`]. Nodes only refer to
+/// nodes created before them.
+pub(super) struct Formulas {
+    nodes: Vec<Node>,
+    cache: HashMap<Node, Id>,
+}
+
+/// Bits of a register as formulas, lowest bit first
+pub(super) type Bits = Vec<Id>;
+
+impl Formulas {
+    pub fn new() -> Self {
+        let mut f = Self {
+            nodes: Vec::new(),
+            cache: HashMap::new(),
+        };
+        f.add(Node::Const(false));
+        f.add(Node::Const(true));
+        f
+    }
+
+    fn add(&mut self, node: Node) -> Id {
+        if let Some(id) = self.cache.get(&node) {
+            return *id;
+        }
+        self.nodes.push(node);
+        self.cache.insert(node, self.nodes.len() - 1);
+        self.nodes.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn var(&mut self, bit: usize) -> Id {
+        self.add(Node::Var(bit))
+    }
+
+    pub fn not(&mut self, a: Id) -> Id {
+        match self.nodes[a] {
+            Node::Const(v) => self.add(Node::Const(!v)),
+            Node::Not(x) => x,
+            _ => self.add(Node::Not(a)),
+        }
+    }
+
+    pub fn and(&mut self, a: Id, b: Id) -> Id {
+        let (a, b) = (a.min(b), a.max(b));
+        match (a, b) {
+            (F, _) => F,
+            (T, b) => b,
+            _ if a == b => a,
+            _ if self.nodes[b] == Node::Not(a) => F,
+            _ => self.add(Node::And(a, b)),
+        }
+    }
+
+    pub fn or(&mut self, a: Id, b: Id) -> Id {
+        let (na, nb) = (self.not(a), self.not(b));
+        let both = self.and(na, nb);
+        self.not(both)
+    }
+
+    pub fn xor(&mut self, a: Id, b: Id) -> Id {
+        let (a, b) = (a.min(b), a.max(b));
+        match (a, b) {
+            (F, b) => b,
+            (T, b) => self.not(b),
+            _ if a == b => F,
+            _ => self.add(Node::Xor(a, b)),
+        }
+    }
+
+    /// `a` if `s` is set, `b` otherwise
+    pub fn mux(&mut self, s: Id, a: Id, b: Id) -> Id {
+        if a == b {
+            return a;
+        }
+        let ns = self.not(s);
+        let (a, b) = (self.and(s, a), self.and(ns, b));
+        self.or(a, b)
+    }
+
+    pub fn constant(&self, value: usize, width: usize) -> Bits {
+        (0..width)
+            .map(|i| if value >> i & 1 == 1 { T } else { F })
+            .collect()
+    }
+
+    /// Formula that holds when `bits` are equal to `value`
+    pub fn equals(&mut self, bits: &[Id], value: usize) -> Id {
+        let mut res = T;
+        for (i, &bit) in bits.iter().enumerate() {
+            let bit = if value >> i & 1 == 1 {
+                bit
+            } else {
+                self.not(bit)
+            };
+            res = self.and(res, bit);
+        }
+        if bits.len() < usize::BITS as usize && value >> bits.len() != 0 {
+            return F;
+        }
+        res
+    }
+
+    fn any(&mut self, bits: &[Id]) -> Id {
+        bits.iter().fold(F, |acc, &b| self.or(acc, b))
+    }
+
+    fn shr(&mut self, x: &[Id], amount: &[Id]) -> Bits {
+        let mut x = x.to_vec();
+        for (k, &bit) in amount.iter().enumerate() {
+            if bit == F {
+                continue;
+            }
+            if k >= usize::BITS.ilog2() as usize || 1 << k >= x.len() {
+                let nbit = self.not(bit);
+                x = x.iter().map(|&b| self.and(nbit, b)).collect();
+            } else {
+                let shifted = (0..x.len())
+                    .map(|i| x.get(i + (1 << k)).copied().unwrap_or(F))
+                    .collect::<Vec<_>>();
+                x = (0..x.len())
+                    .map(|i| self.mux(bit, shifted[i], x[i]))
+                    .collect();
+            }
+        }
+        x
+    }
+
+    /// Value of a formula for a partial assignment of bits of A, `None` if it depends on bits
+    /// that are not assigned yet
+    pub fn eval(&self, id: Id, bits: &[Option<bool>]) -> Option<bool> {
+        let mut vals: Vec<Option<bool>> = Vec::with_capacity(id + 1);
+        for node in &self.nodes[..=id] {
+            let v = match *node {
+                Node::Const(v) => Some(v),
+                Node::Var(bit) => bits.get(bit).copied().flatten(),
+                Node::Not(a) => vals[a].map(|a| !a),
+                Node::And(a, b) => match (vals[a], vals[b]) {
+                    (Some(false), _) | (_, Some(false)) => Some(false),
+                    (Some(true), Some(true)) => Some(true),
+                    _ => None,
+                },
+                Node::Xor(a, b) => match (vals[a], vals[b]) {
+                    (Some(a), Some(b)) => Some(a ^ b),
+                    _ => None,
+                },
+            };
+            vals.push(v);
+        }
+        vals[id]
+    }
+
+    /// Smallest value of A with `width` bits that satisfies a formula: bits are assigned from the
+    /// top, zeros first, backtracking as soon as the formula becomes false
+    pub fn smallest(&self, goal: Id, width: usize) -> Option<usize> {
+        fn go(f: &Formulas, goal: Id, bits: &mut Vec<Option<bool>>, bit: usize) -> bool {
+            match f.eval(goal, bits) {
+                Some(false) => return false,
+                Some(true) => return true,
+                None if bit == 0 => return false,
+                None => {}
+            }
+            for v in [false, true] {
+                bits[bit - 1] = Some(v);
+                if go(f, goal, bits, bit - 1) {
+                    return true;
+                }
+            }
+            bits[bit - 1] = None;
+            false
+        }
+
+        let mut bits = vec![None; width];
+        go(self, goal, &mut bits, width).then(|| {
+            // bits left unassigned don't matter, zero is the smallest choice
+            let set = bits.iter().enumerate().filter(|b| *b.1 == Some(true));
+            set.map(|(i, _)| 1 << i).sum()
+        })
+    }
+
+    /// Same as [`Formulas::smallest`], but by trying every value, only viable for narrow A
+    #[cfg(test)]
+    pub fn brute(&self, goal: Id, width: usize) -> Option<usize> {
+        (0..1usize << width).find(|a| {
+            let bits = (0..width)
+                .map(|i| Some(a >> i & 1 == 1))
+                .collect::<Vec<_>>();
+            self.eval(goal, &bits) == Some(true)
+        })
+    }
+
+    /// Formula as text, bits of A are `a0`, `a1`, ...
+    pub fn show(&self, id: Id) -> String {
+        match self.nodes[id] {
+            Node::Const(v) => (v as u8).to_string(),
+            Node::Var(bit) => format!("a{bit}"),
+            Node::Not(a) => format!("!{}", self.show(a)),
+            Node::And(a, b) => format!("({} & {})", self.show(a), self.show(b)),
+            Node::Xor(a, b) => format!("({} ^ {})", self.show(a), self.show(b)),
+        }
+    }
+}
+
+/// One way through the program
+#[derive(Debug, Clone)]
+pub(super) struct Path {
+    /// Formula that holds for values of A that take this path
+    pub cond: Id,
+    /// Output digits, 3 bits each
+    pub out: Vec<[Id; 3]>,
+}
+
+/// All the ways a program can run
+pub(super) struct Run {
+    pub formulas: Formulas,
+    /// Paths that halted
+    pub paths: Vec<Path>,
+    /// Number of paths that took too many steps and were dropped
+    pub cut: usize,
+    pub width: usize,
+}
+
+#[derive(Clone)]
+struct State {
+    a: Bits,
+    b: Bits,
+    c: Bits,
+    ip: usize,
+    steps: usize,
+    path: Path,
+}
+
+/// Run the program with A as `width` unknown bits, B and C come from the puter. Paths longer than
+/// `max_steps` instructions are dropped.
+pub(super) fn run(puter: &Puter, width: usize, max_steps: usize) -> Run {
+    let mut f = Formulas::new();
+    // registers are wide enough for all of A and for B and C as they start
+    let bits = |v: usize| (usize::BITS - v.leading_zeros()) as usize;
+    let regs = width.max(bits(puter.b)).max(bits(puter.c));
+    let a = (0..regs)
+        .map(|bit| if bit < width { f.var(bit) } else { F })
+        .collect();
+    let start = State {
+        a,
+        b: f.constant(puter.b, regs),
+        c: f.constant(puter.c, regs),
+        ip: 0,
+        steps: 0,
+        path: Path {
+            cond: T,
+            out: Vec::new(),
+        },
+    };
+
+    let mut paths = Vec::new();
+    let mut cut = 0;
+    let mut todo = vec![start];
+    while let Some(mut s) = todo.pop() {
+        loop {
+            let Some(instr) = puter.code.0.get(s.ip).copied() else {
+                paths.push(s.path);
+                break;
+            };
+            if s.steps == max_steps {
+                cut += 1;
+                break;
+            }
+            s.steps += 1;
+            s.ip += 1;
+            let combo = |f: &Formulas, s: &State, op: CoOp| match op {
+                CoOp::Lit(l) => f.constant(l, 3),
+                CoOp::A => s.a.clone(),
+                CoOp::B => s.b.clone(),
+                CoOp::C => s.c.clone(),
+            };
+            match instr {
+                Instr::Adv(op) => {
+                    let amount = combo(&f, &s, op);
+                    s.a = f.shr(&s.a, &amount);
+                }
+                Instr::Bdv(op) => {
+                    let amount = combo(&f, &s, op);
+                    s.b = f.shr(&s.a, &amount);
+                }
+                Instr::Cdv(op) => {
+                    let amount = combo(&f, &s, op);
+                    s.c = f.shr(&s.a, &amount);
+                }
+                Instr::Bxl(l) => {
+                    let l = f.constant(l, regs);
+                    s.b = (0..regs).map(|i| f.xor(s.b[i], l[i])).collect();
+                }
+                Instr::Bst(op) => {
+                    let v = combo(&f, &s, op);
+                    s.b = (0..regs).map(|i| if i < 3 { v[i] } else { F }).collect();
+                }
+                Instr::Bxc(_) => s.b = (0..regs).map(|i| f.xor(s.b[i], s.c[i])).collect(),
+                Instr::Out(op) => {
+                    let v = combo(&f, &s, op);
+                    s.path.out.push([v[0], v[1], v[2]]);
+                }
+                Instr::Jnz(target) => {
+                    let nonzero = f.any(&s.a);
+                    let zero = f.not(nonzero);
+                    let (jump, stay) = (f.and(s.path.cond, nonzero), f.and(s.path.cond, zero));
+                    if jump != F && stay != F {
+                        let mut other = s.clone();
+                        other.path.cond = stay;
+                        todo.push(other);
+                    }
+                    if jump != F {
+                        s.path.cond = jump;
+                        s.ip = target / 2;
+                    } else {
+                        s.path.cond = stay;
+                    }
+                }
+            }
+        }
+    }
+    Run {
+        formulas: f,
+        paths,
+        cut,
+        width,
+    }
+}
+
+impl Run {
+    /// Formula for values of A that take a given path and output exactly `want`
+    pub fn output_is(&mut self, path: usize, want: &[usize]) -> Id {
+        let Path { cond, out } = self.paths[path].clone();
+        if out.len() != want.len() {
+            return F;
+        }
+        out.iter().zip(want).fold(cond, |acc, (digit, w)| {
+            let eq = self.formulas.equals(digit, *w);
+            self.formulas.and(acc, eq)
+        })
+    }
+
+    /// Smallest A that makes the program output exactly `want`
+    pub fn solve(&mut self, want: &[usize]) -> Option<usize> {
+        (0..self.paths.len())
+            .filter_map(|path| {
+                let goal = self.output_is(path, want);
+                self.formulas.smallest(goal, self.width)
+            })
+            .min()
+    }
+
+    /// Digits that can appear at each position of the output of a given path
+    pub fn reachable(&mut self, path: usize) -> Vec<Vec<usize>> {
+        let Path { cond, out } = self.paths[path].clone();
+        out.iter()
+            .map(|digit| {
+                (0..8)
+                    .filter(|d| {
+                        let eq = self.formulas.equals(digit, *d);
+                        let goal = self.formulas.and(cond, eq);
+                        self.formulas.smallest(goal, self.width).is_some()
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Smallest A that makes the program output itself, found symbolically
+pub(super) fn quine(input: &Puter) -> Option<usize> {
+    let want = input
+        .raw
+        .split(',')
+        .map(|d| d.parse().unwrap())
+        .collect::<Vec<_>>();
+    run(input, want.len() * 3, 10_000).solve(&want)
+}
+
+/// Paths through a program along with reachable output digits, or smallest A for given output
+pub(super) fn symbolic(year: &Year, args: &[String]) -> Result<(), String> {
+    let (file, want) = match args {
+        [file, want] if want.contains(',') || want.parse::<usize>().is_ok() => {
+            (std::slice::from_ref(file), Some(want))
+        }
+        [want] if want.contains(',') => (&[][..], Some(want)),
+        args => (args, None),
+    };
+    let puter = super::parse(&super::source(year, file)?);
+    let width = 3 * puter.raw.split(',').count();
+    let mut run = run(&puter, width, 10_000);
+    println!(
+        "{} paths with {width} bits of A, {} formulas",
+        run.paths.len(),
+        run.formulas.len()
+    );
+    if run.cut > 0 {
+        println!(
+            "{} paths take more than 10000 steps and were dropped",
+            run.cut
+        );
+    }
+
+    if let Some(want) = want {
+        let want = want
+            .split(',')
+            .map(|d| d.parse().map_err(|_| format!("Bad digit: {d}")))
+            .collect::<Result<Vec<usize>, _>>()?;
+        match run.solve(&want) {
+            Some(a) => println!("smallest A: {a}"),
+            None => println!("no A with {width} bits produces this output"),
+        }
+        return Ok(());
+    }
+
+    for ix in 0..run.paths.len() {
+        let path = &run.paths[ix];
+        let smallest = run.formulas.smallest(path.cond, width);
+        let Some(smallest) = smallest else {
+            continue;
+        };
+        println!("{} digits, smallest A: {smallest}", path.out.len());
+        let digits = run.reachable(ix);
+        for (pos, digits) in digits.iter().enumerate() {
+            let bits = run.paths[ix].out[pos].map(|b| run.formulas.show(b));
+            let bits = if bits.iter().any(|b| b.len() > 60) {
+                String::new()
+            } else {
+                format!(" = {}", bits.join(", "))
+            };
+            println!("\t{pos}: {digits:?}{bits}");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn formulas_work() {
+    let mut f = Formulas::new();
+    let (a, b) = (f.var(0), f.var(1));
+    let x = f.xor(a, b);
+    assert_eq!(f.xor(x, x), F);
+    assert_eq!(f.xor(a, F), a);
+    let na = f.not(a);
+    assert_eq!(f.and(a, na), F);
+    assert_eq!(f.not(na), a);
+
+    let ab = [a, b];
+    let two = f.equals(&ab, 2);
+    assert_eq!(f.smallest(two, 2), Some(2));
+    assert_eq!(f.brute(two, 2), Some(2));
+    assert_eq!(f.eval(two, &[Some(true), None]), Some(false));
+    assert_eq!(f.eval(two, &[Some(false), None]), None);
+    assert_eq!(f.equals(&ab, 4), F);
+}
+
+#[test]
+fn symbolic_matches_eval() {
+    let src = "bst A\nbxl 3\ncdv B\nadv 3\nbxc\nbxl 5\nout B\njnz 0";
+    let code = super::Program::assemble(src).unwrap().encode();
+    let puter = super::parse(&format!(
+        "Register A: 0\nRegister B: 0\nRegister C: 0\n\nProgram: {code}"
+    ));
+    let mut run = run(&puter, 12, 1000);
+    assert_eq!(run.cut, 0);
+    for a in [1, 7, 100, 1234, 4095] {
+        let mut p = puter.clone();
+        p.a = a;
+        let mut want = Vec::new();
+        let trace = crate::trace::Trace::default();
+        super::eval(
+            &mut p,
+            &mut |d| want.push(d as usize),
+            &mut super::Never,
+            super::Budget::NONE,
+            &trace,
+        );
+        let found = run.solve(&want).unwrap();
+        assert!(found <= a);
+
+        let path = run.paths.iter().position(|p| p.out.len() == want.len());
+        let goal = run.output_is(path.unwrap(), &want);
+        assert_eq!(run.formulas.brute(goal, 12), Some(found));
+    }
+}
+
+#[test]
+fn wide_registers_work() {
+    // B is wider than A, shifting by it clears C whatever A is
+    let code = super::Program::assemble("cdv B\nout C").unwrap().encode();
+    let puter = super::parse(&format!(
+        "Register A: 0\nRegister B: 1024\nRegister C: 0\n\nProgram: {code}"
+    ));
+    let mut run = run(&puter, 6, 100);
+    assert_eq!(run.solve(&[5]), None);
+    assert_eq!(run.solve(&[0]), Some(0));
+}
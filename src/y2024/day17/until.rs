@@ -0,0 +1,106 @@
+//! When [`eval`](super::eval) should stop before the program halts
+//!
+//! Searches over A usually know after a digit or two that a candidate is no good, predicates
+//! here let them stop right there. Tuples combine predicates, running stops when any of them
+//! says so. Limits on steps are part of [`Budget`](super::Budget).
+
+use super::Puter;
+
+pub(super) trait Until {
+    /// Called after every executed instruction with the digit it output, if any
+    fn stop(&mut self, puter: &Puter, digit: Option<u8>) -> bool;
+}
+
+/// Run until the program halts
+pub(super) struct Never;
+
+impl Until for Never {
+    fn stop(&mut self, _: &Puter, _: Option<u8>) -> bool {
+        false
+    }
+}
+
+/// Stop as soon as the output stops being a prefix of `want`
+pub(super) struct Prefix<'a> {
+    want: &'a [u8],
+    seen: usize,
+}
+
+impl<'a> Prefix<'a> {
+    pub fn new(want: &'a [u8]) -> Self {
+        Self { want, seen: 0 }
+    }
+
+    /// Output so far matches the start of `want`
+    pub fn matched(&self) -> usize {
+        self.seen
+    }
+}
+
+impl Until for Prefix<'_> {
+    fn stop(&mut self, _: &Puter, digit: Option<u8>) -> bool {
+        let Some(digit) = digit else {
+            return false;
+        };
+        if self.want.get(self.seen) == Some(&digit) {
+            self.seen += 1;
+            false
+        } else {
+            true
+        }
+    }
+}
+
+/// Stop after this many digits
+pub(super) struct MaxOut(pub usize);
+
+impl Until for MaxOut {
+    fn stop(&mut self, _: &Puter, digit: Option<u8>) -> bool {
+        if digit.is_some() {
+            self.0 = self.0.saturating_sub(1);
+        }
+        self.0 == 0
+    }
+}
+
+impl<A: Until, B: Until> Until for (A, B) {
+    fn stop(&mut self, puter: &Puter, digit: Option<u8>) -> bool {
+        // both get to see every step so counters stay right
+        let a = self.0.stop(puter, digit);
+        let b = self.1.stop(puter, digit);
+        a || b
+    }
+}
+
+#[test]
+fn predicates_work() {
+    use super::{eval, parse, Budget, Outcome};
+    use crate::trace::Trace;
+
+    // digits of A lowest first, one every 3 instructions
+    let puter = parse("Register A: 83\nRegister B: 0\nRegister C: 0\n\nProgram: 5,4,0,3,3,0");
+    let run = |until: &mut dyn Until| {
+        let mut puter = puter.clone();
+        let mut out = Vec::new();
+        let halted = eval(
+            &mut puter,
+            &mut |d| out.push(d),
+            until,
+            Budget::NONE,
+            &Trace::default(),
+        ) == Outcome::Halted;
+        (out, halted)
+    };
+
+    assert_eq!(run(&mut Never), (vec![3, 2, 1], true));
+    assert_eq!(run(&mut MaxOut(2)), (vec![3, 2], false));
+    let mut prefix = Prefix::new(&[3, 2, 7]);
+    assert_eq!(run(&mut prefix), (vec![3, 2, 1], false));
+    assert_eq!(prefix.matched(), 2);
+    let mut prefix = Prefix::new(&[3, 2, 1]);
+    assert_eq!(run(&mut prefix), (vec![3, 2, 1], true));
+    assert_eq!(
+        run(&mut (MaxOut(3), Prefix::new(&[3, 7]))),
+        (vec![3, 2], false)
+    );
+}
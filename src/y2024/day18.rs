@@ -0,0 +1,210 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+};
+
+use crate::{
+    runner::{params, Solution},
+    trace::{Event, Level, Trace},
+    Point, TwoDee,
+};
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(18, 1, |i, p| part1(&parse(i), &p.get()).to_string()),
+    Solution::new(18, 2, |i, p| part2(&parse(i), &p.get(), &p.trace)),
+    // not part of the puzzle: part 1 with a byte falling after every step
+    Solution::new(18, 3, |i, p| {
+        part3(&parse(i), &p.get(), &p.trace).to_string()
+    })
+    .named("falling"),
+];
+
+params! {
+    /// Width and height of the memory space
+    size: i32 = 71,
+    /// Bytes fallen before part 1 and 3 start walking
+    bytes: usize = 1024,
+}
+
+fn parse(input: &str) -> Vec<(i32, i32)> {
+    input
+        .lines()
+        .map(|l| {
+            let (x, y) = l.split_once(',').unwrap();
+            (x.parse().unwrap(), y.parse().unwrap())
+        })
+        .collect()
+}
+
+fn part1(input: &[(i32, i32)], params: &Params) -> usize {
+    go(&input[..params.bytes], params.size).expect("exit is blocked")
+}
+
+/// Steps from the top left corner to the bottom right one, `None` if it can't be reached
+fn go(input: &[(i32, i32)], size: i32) -> Option<usize> {
+    let mut m = TwoDee::<bool>::new(size as usize);
+    let mut steps = TwoDee::<usize>::new(size as usize);
+
+    for (x, y) in input.iter().copied() {
+        let p = Point { x, y };
+        m[p] = true;
+    }
+
+    for x in 0..size {
+        for y in 0..size {
+            let p = Point { x, y };
+            steps[p] = 999_999;
+        }
+    }
+    let start = Point { x: 0, y: 0 };
+    steps[start] = 0;
+    let mut q = VecDeque::new();
+    q.push_back(start);
+
+    while let Some(p) = q.pop_front() {
+        for d in Point::DIRS {
+            if m.get_point(p + d).copied().unwrap_or(true) {
+                continue;
+            }
+            if steps[p + d] > steps[p] + 1 {
+                steps[p + d] = steps[p] + 1;
+                q.push_back(p + d);
+            }
+        }
+    }
+    let steps = steps[(size as usize - 1, size as usize - 1)];
+    (steps < 999_999).then_some(steps)
+}
+
+/// Same walk as [`part1`], with one more byte falling after every step
+fn part3(input: &[(i32, i32)], params: &Params, trace: &Trace) -> usize {
+    let path = falling(input, params).expect("trapped before reaching the exit");
+    let time = path.len() - 1;
+    let fallen = &input[..(params.bytes + time).min(input.len())];
+    let walk = Walk {
+        size: params.size,
+        fallen,
+        path: &path,
+    };
+    trace.emit(Level::Info, || Event::Frame {
+        step: time,
+        grid: &walk,
+    });
+    time
+}
+
+/// Shortest way to the exit when one more byte falls after every step, starting with `bytes`
+/// of them down, `None` if the walker gets trapped. Every position along the way, one per step.
+///
+/// States are `(Point, t)`, but memory only gets more corrupted, so reaching a point later is
+/// never better than reaching it earlier: the first visit is the one to keep and standing still
+/// doesn't help either.
+fn falling(input: &[(i32, i32)], params: &Params) -> Option<Vec<Point>> {
+    let size = params.size;
+    // time each cell is corrupted at, bytes fall after a step
+    let mut fall = TwoDee::<usize>::new(size as usize).map(|_| usize::MAX);
+    for (ix, (x, y)) in input.iter().copied().enumerate().rev() {
+        fall[Point { x, y }] = (ix + 1).saturating_sub(params.bytes);
+    }
+    let start = Point { x: 0, y: 0 };
+    let exit = Point {
+        x: size - 1,
+        y: size - 1,
+    };
+    if fall[start] == 0 {
+        return None;
+    }
+    let mut prev = HashMap::from([(start, start)]);
+    let mut q = VecDeque::from([(start, 0)]);
+    while let Some((p, t)) = q.pop_front() {
+        if p == exit {
+            let mut path = vec![p];
+            while path[path.len() - 1] != start {
+                path.push(prev[&path[path.len() - 1]]);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for d in Point::DIRS {
+            let next = p + d;
+            let free = fall.get_point(next).is_some_and(|&f| f > t + 1);
+            if free && !prev.contains_key(&next) {
+                prev.insert(next, p);
+                q.push_back((next, t + 1));
+            }
+        }
+    }
+    None
+}
+
+/// Memory with the bytes fallen so far and the way the walker went
+struct Walk<'a> {
+    size: i32,
+    fallen: &'a [(i32, i32)],
+    path: &'a [Point],
+}
+
+impl fmt::Debug for Walk<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f)?;
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let c = if self.path.contains(&Point { x, y }) {
+                    'O'
+                } else if self.fallen.contains(&(x, y)) {
+                    '#'
+                } else {
+                    '.'
+                };
+                write!(f, "{c}")?;
+            }
+            writeln!(f)?;
+        }
+        Ok(())
+    }
+}
+
+fn part2(input: &[(i32, i32)], params: &Params, trace: &Trace) -> String {
+    let (ix, (x, y)) = first_block(input, params.size).expect("exit is never blocked");
+    trace.emit(Level::Info, || {
+        Event::Note(format!("byte {ix} is the first to block the exit"))
+    });
+    format!("{x},{y}")
+}
+
+/// First byte that cuts the exit off along with its index. Once blocked the exit stays blocked,
+/// so the number of bytes it takes is a binary search away.
+fn first_block(input: &[(i32, i32)], size: i32) -> Option<(usize, (i32, i32))> {
+    // fewest bytes that block the exit is in lo..hi, `input.len() + 1` if none of them do
+    let (mut lo, mut hi) = (0, input.len() + 1);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if go(&input[..mid], size).is_some() {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    let ix = lo.checked_sub(1).filter(|&ix| ix < input.len())?;
+    Some((ix, input[ix]))
+}
+
+#[test]
+fn first_block_works() {
+    let input = parse(&crate::y2024::YEAR.example("day18").input);
+    assert_eq!(first_block(&input, 7), Some((20, (6, 1))));
+    assert_eq!(first_block(&input[..20], 7), None);
+    assert_eq!(first_block(&[], 7), None);
+    // exit itself
+    assert_eq!(first_block(&[(1, 1), (6, 6)], 7), Some((1, (6, 6))));
+}
+
+#[test]
+fn falling_works() {
+    let input = parse(&crate::y2024::YEAR.example("day18").input);
+    let params = |bytes| Params { size: 7, bytes };
+    let path = falling(&input, &params(0)).unwrap();
+    assert_eq!((path[0], path.len() - 1), (Point { x: 0, y: 0 }, 12));
+    // bytes close in faster than the walker gets away
+    assert_eq!(falling(&input, &params(10)), None);
+}
@@ -0,0 +1,140 @@
+use std::collections::BTreeSet;
+
+use crate::{
+    fuzz::{Fuzz, Rng},
+    runner::Solution,
+};
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(19, 1, |i, _| part1(&parse(i)).to_string()),
+    Solution::new(19, 1, |i, _| part1dp(&parse(i)).to_string()).named("dp"),
+    Solution::new(19, 2, |i, _| part2(&parse(i)).to_string()),
+];
+
+pub(crate) const FUZZ: Fuzz = Fuzz {
+    day: 19,
+    generate,
+    shrink,
+    params: &[],
+};
+
+fn parse(input: &str) -> (BTreeSet<String>, Vec<String>) {
+    let mut lines = input.lines();
+    let pats = lines
+        .next()
+        .unwrap()
+        .split(", ")
+        .map(|m| m.to_owned())
+        .collect::<BTreeSet<_>>();
+
+    lines.next();
+    let qs = lines.map(|m| m.to_owned()).collect::<Vec<_>>();
+    (pats, qs)
+}
+
+fn part1(input: &(BTreeSet<String>, Vec<String>)) -> usize {
+    let (pats, qs) = input;
+    let max_pat = pats.iter().map(|l| l.len()).max().unwrap_or_default();
+
+    let mut v = 0;
+    for q in qs {
+        v += go1(pats, max_pat, q);
+    }
+    v
+}
+
+/// Same as part1, but using counting from part2
+fn part1dp(input: &(BTreeSet<String>, Vec<String>)) -> usize {
+    let (pats, qs) = input;
+    let max_pat = pats.iter().map(|l| l.len()).max().unwrap_or_default();
+    qs.iter().filter(|q| go(pats, max_pat, q) > 0).count()
+}
+
+fn go1(available: &BTreeSet<String>, max: usize, q: &str) -> usize {
+    if q.is_empty() {
+        return 1;
+    }
+
+    for prefix in 1..=max {
+        let Some((head, tail)) = q.split_at_checked(prefix) else {
+            return 0;
+        };
+
+        if available.contains(head) {
+            let n = go1(available, max, tail);
+            if n > 0 {
+                return n;
+            }
+        }
+    }
+    0
+}
+
+fn go(avail: &BTreeSet<String>, max: usize, q: &str) -> usize {
+    let mut r = vec![0; q.len() + 1];
+
+    r[0] = 1;
+    'outer: for pos in 0..q.len() {
+        for prefix in 1..=max {
+            if pos + prefix > q.len() {
+                continue 'outer;
+            }
+            let Some((head, _)) = q[pos..].split_at_checked(prefix) else {
+                continue 'outer;
+            };
+            if avail.contains(head) {
+                r[pos + prefix] += r[pos];
+            }
+        }
+    }
+    r[q.len()]
+}
+
+fn part2(input: &(BTreeSet<String>, Vec<String>)) -> usize {
+    let (pats, qs) = input;
+    let max_pat = pats.iter().map(|l| l.len()).max().unwrap_or_default();
+
+    let mut v = 0;
+    for q in qs {
+        v += go(pats, max_pat, q);
+    }
+    v
+}
+
+fn generate(rng: &mut Rng) -> String {
+    fn word(rng: &mut Rng, max: usize) -> String {
+        (0..rng.range(1..=max))
+            .map(|_| rng.pick(&['w', 'u', 'b', 'r', 'g']))
+            .collect()
+    }
+    let pats = (0..rng.range(1..=8))
+        .map(|_| word(rng, 3))
+        .collect::<Vec<_>>();
+    let qs = (0..rng.range(1..=5))
+        .map(|_| word(rng, 12))
+        .collect::<Vec<_>>();
+    format!("{}\n\n{}", pats.join(", "), qs.join("\n"))
+}
+
+/// Drop either a pattern or a design
+fn shrink(input: &str) -> Vec<String> {
+    let (pats, qs) = input.split_once("\n\n").unwrap();
+    let pats = pats.split(", ").collect::<Vec<_>>();
+    let qs = qs.lines().collect::<Vec<_>>();
+    let mut res = Vec::new();
+    for skip in 0..pats.len() {
+        let mut pats = pats.clone();
+        pats.remove(skip);
+        if !pats.is_empty() {
+            res.push(format!("{}\n\n{}", pats.join(", "), qs.join("\n")));
+        }
+    }
+    for skip in 0..qs.len() {
+        let mut qs = qs.clone();
+        qs.remove(skip);
+        if !qs.is_empty() {
+            res.push(format!("{}\n\n{}", pats.join(", "), qs.join("\n")));
+        }
+    }
+    res
+}
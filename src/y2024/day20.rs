@@ -1,9 +1,18 @@
-use std::collections::BTreeMap;
-
-use aoc_runner_derive::{aoc, aoc_generator};
+use crate::{
+    runner::{params, Solution},
+    Point, TwoDee,
+};
+
+pub(crate) const SOLUTIONS: &[Solution] = &[
+    Solution::new(20, 1, |i, p| part1(&parse(i), &p.get()).to_string()),
+    Solution::new(20, 2, |i, p| part2(&parse(i), &p.get()).to_string()),
+];
+
+params! {
+    /// Count only cheats that save at least that many picoseconds
+    cutoff: usize = 100,
+}
 
-use crate::{Point, TwoDee};
-#[aoc_generator(day20)]
 fn parse(input: &str) -> (TwoDee<bool>, Point, Point) {
     let m = input.lines().map(|l| l.bytes()).collect::<TwoDee<u8>>();
 
@@ -120,61 +129,10 @@ fn go2(input: &(TwoDee<bool>, Point, Point), cutoff: usize) -> usize {
     c
 }
 
-#[aoc(day20, part1)]
-fn part1(input: &(TwoDee<bool>, Point, Point)) -> usize {
-    go1(input, 100)
+fn part1(input: &(TwoDee<bool>, Point, Point), params: &Params) -> usize {
+    go1(input, params.cutoff)
 }
 
-#[aoc(day20, part2)]
-fn part2(input: &(TwoDee<bool>, Point, Point)) -> usize {
-    go2(input, 100)
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    #[test]
-    fn part1_example() {
-        let input = "\
-###############
-#...#...#.....#
-#.#.#.#.#.###.#
-#S#...#.#.#...#
-#######.#.#.###
-#######.#.#...#
-#######.#.###.#
-###..E#...#...#
-###.#######.###
-#...###...#...#
-#.#####.#.###.#
-#.#...#.#.#...#
-#.#.#.#.#.#.###
-#...#...#...###
-###############";
-        assert_eq!(go1(&parse(input), 20), 5);
-    }
-
-    #[test]
-    fn part2_example() {
-        let p = Point { x: 10, y: 10 };
-
-        let input = "\
-###############
-#...#...#.....#
-#.#.#.#.#.###.#
-#S#...#.#.#...#
-#######.#.#.###
-#######.#.#...#
-#######.#.###.#
-###..E#...#...#
-###.#######.###
-#...###...#...#
-#.#####.#.###.#
-#.#...#.#.#...#
-#.#.#.#.#.#.###
-#...#...#...###
-###############";
-        assert_eq!(go2(&parse(input), 70), 12 + 22 + 4 + 3);
-    }
+fn part2(input: &(TwoDee<bool>, Point, Point), params: &Params) -> usize {
+    go2(input, params.cutoff)
 }
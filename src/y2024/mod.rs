@@ -0,0 +1,67 @@
+//! Advent of code 2024
+
+use crate::runner::Year;
+
+mod day01;
+mod day02;
+mod day03;
+mod day04;
+mod day05;
+mod day06;
+mod day07;
+mod day08;
+mod day09;
+mod day10;
+mod day11;
+mod day12;
+mod day13;
+mod day14;
+mod day15;
+mod day16;
+mod day17;
+mod day18;
+mod day19;
+mod day20;
+
+pub(crate) const YEAR: Year = Year {
+    year: 2024,
+    days: &[
+        day01::SOLUTIONS,
+        day02::SOLUTIONS,
+        day03::SOLUTIONS,
+        day04::SOLUTIONS,
+        day05::SOLUTIONS,
+        day06::SOLUTIONS,
+        day07::SOLUTIONS,
+        day08::SOLUTIONS,
+        day09::SOLUTIONS,
+        day10::SOLUTIONS,
+        day11::SOLUTIONS,
+        day12::SOLUTIONS,
+        day13::SOLUTIONS,
+        day14::SOLUTIONS,
+        day15::SOLUTIONS,
+        day16::SOLUTIONS,
+        day17::SOLUTIONS,
+        day18::SOLUTIONS,
+        day19::SOLUTIONS,
+        day20::SOLUTIONS,
+    ],
+    fuzz: &[
+        day10::FUZZ,
+        day11::FUZZ,
+        day13::FUZZ,
+        day15::FUZZ,
+        day19::FUZZ,
+    ],
+    tools: &[day15::TOOLS, day16::TOOLS, day17::TOOLS],
+    params: &[
+        (8, day08::PARAMS),
+        (11, day11::PARAMS),
+        (14, day14::PARAMS),
+        (15, day15::PARAMS),
+        (16, day16::PARAMS),
+        (18, day18::PARAMS),
+        (20, day20::PARAMS),
+    ],
+};